@@ -0,0 +1,221 @@
+//! Benchmarks each part of the current event the way the benchmark service's `dayN::part1` and
+//! `dayN::part2` entry points run it, parsing included, against local inputs without needing the
+//! service.
+//!
+//! ```text
+//! cargo bench --bench parts -- [--samples N] [--warmup N] [--input-dir DIR]
+//!     [--save-baseline PATH] [--baseline PATH] [FILTER]...
+//! ```
+//!
+//! Inputs are read from `DIR/2024/dayN.txt` [default: `input`], days without one are skipped.
+//! Only functions with a name like `day6/part1` containing one of the filters are run.
+//!
+//! `--save-baseline` writes the stats in the same CSV format as `aoc-2024 bench --save-baseline`,
+//! with the mode `parts`, and `--baseline` compares each function against a saved CSV by Welch's
+//! t-test like `aoc-2024 bench --baseline`, so results can be compared across commits.
+
+use aoc_2024::baseline::{self, Baseline, Change, Environment, Row};
+use aoc_2024::{days_for_year, latest_year, Answer, ParseError, RunnerError, Stats};
+use std::collections::HashMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// The mode of the rows the harness saves, apart from those of `aoc-2024 bench`
+const MODE: &str = "parts";
+
+struct Function {
+    name: String,
+    day: usize,
+    /// `part1` or `part2`
+    part: &'static str,
+    /// Parses the input and solves the part, like `dayN::partN`
+    solve: fn(&str) -> Result<Answer, ParseError>,
+}
+
+/// Both parts of every implemented day of the current event
+fn functions() -> Vec<Function> {
+    let days = days_for_year(latest_year()).expect("the latest year has solutions");
+    (1..)
+        .zip(days)
+        .filter_map(|(day, solution)| Some((day, (*solution)?)))
+        .flat_map(|(day, solution)| {
+            [("part1", solution.part1), ("part2", solution.part2)].map(|(part, solve)| Function {
+                name: format!("day{day}/{part}"),
+                day,
+                part,
+                solve,
+            })
+        })
+        .collect()
+}
+
+#[inline]
+fn time<O>(f: impl FnOnce() -> O) -> Duration {
+    let start = Instant::now();
+    let output = black_box(f());
+    let elapsed = start.elapsed();
+    drop(output);
+    elapsed
+}
+
+struct Args {
+    samples: u32,
+    warmup: u32,
+    input_dir: PathBuf,
+    save_baseline: Option<String>,
+    baseline: Option<String>,
+    filters: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut result = Self {
+            samples: 100,
+            warmup: 10,
+            input_dir: PathBuf::from("input"),
+            save_baseline: None,
+            baseline: None,
+            filters: Vec::new(),
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {arg}"))
+            };
+            match arg.as_str() {
+                // Passed by `cargo bench`
+                "--bench" => {}
+                "--samples" | "--warmup" => {
+                    let n = value()?;
+                    let n = n
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0 || arg == "--warmup")
+                        .ok_or_else(|| format!("invalid value `{n}` for {arg}"))?;
+                    if arg == "--samples" {
+                        result.samples = n;
+                    } else {
+                        result.warmup = n;
+                    }
+                }
+                "--input-dir" => result.input_dir = PathBuf::from(value()?),
+                "--save-baseline" => result.save_baseline = Some(value()?),
+                "--baseline" => result.baseline = Some(value()?),
+                flag if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+                _ => result.filters.push(arg),
+            }
+        }
+        Ok(result)
+    }
+
+    fn selects(&self, function: &Function) -> bool {
+        self.filters.is_empty()
+            || self
+                .filters
+                .iter()
+                .any(|filter| function.name.contains(filter.as_str()))
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let baseline = args
+        .baseline
+        .as_deref()
+        .map(|path| Baseline::load(path, MODE))
+        .transpose();
+    let baseline = match baseline {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+    let year = latest_year();
+
+    println!(
+        "{:<12} {:>12} {:>12} {:>12} {:>12}",
+        "function", "min", "median", "mean", "stddev"
+    );
+    let mut results = Vec::new();
+    let mut inputs = HashMap::new();
+    let functions = functions();
+    for function in functions.iter().filter(|function| args.selects(function)) {
+        let path = args
+            .input_dir
+            .join(year.to_string())
+            .join(format!("day{}.txt", function.day));
+        let input = inputs
+            .entry(function.day)
+            .or_insert_with(|| fs::read_to_string(&path).ok());
+        let Some(input) = input else {
+            println!(
+                "{:<12} skipped, no input at {}",
+                function.name,
+                path.display()
+            );
+            continue;
+        };
+
+        let answer = match (function.solve)(input) {
+            Ok(answer) => answer,
+            Err(e) => {
+                println!("{:<12} skipped, invalid input at {e}", function.name);
+                continue;
+            }
+        };
+
+        let run = || time(|| (function.solve)(input));
+        for _ in 0..args.warmup {
+            run();
+        }
+        let samples: Vec<Duration> = (0..args.samples).map(|_| run()).collect();
+        let stats = Stats::from_samples(&samples);
+
+        let line = format!(
+            "{:<12} {:>12?} {:>12?} {:>12?} {:>12?}  {}",
+            function.name,
+            stats.min,
+            stats.median,
+            stats.mean,
+            stats.stddev,
+            baseline
+                .as_ref()
+                .and_then(|baseline| baseline.get(year, function.day, function.part))
+                .map(|old| Change::between(old, &stats).to_string())
+                .unwrap_or_default(),
+        );
+        println!("{}", line.trim_end());
+        results.push((function, answer, stats));
+    }
+
+    if let Some(path) = &args.save_baseline {
+        let rows = results.iter().map(|(function, answer, stats)| Row {
+            mode: MODE,
+            year,
+            day: function.day,
+            phase: function.part,
+            answer: Some(answer),
+            stats: *stats,
+        });
+        if let Err(e) = fs::write(path, baseline::csv(&Environment::detect(), rows)) {
+            let e = RunnerError::BaselineFile {
+                path: path.clone(),
+                message: format!("failed to write: {e}"),
+            };
+            eprintln!("error: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+use std::process::Command;
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_owned())
+}
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let rustc_version = command_output(&rustc, &["--version"]);
+    let git_head = command_output("git", &["rev-parse", "HEAD"]);
+
+    println!(
+        "cargo:rustc-env=AOC_RUSTC_VERSION={}",
+        rustc_version.as_deref().unwrap_or("unknown")
+    );
+    println!(
+        "cargo:rustc-env=AOC_GIT_HEAD={}",
+        git_head.as_deref().unwrap_or("unknown")
+    );
+
+    println!("cargo:rerun-if-changed=build.rs");
+    // Rebuild when HEAD moves, either by switching branches or committing
+    for path in [".git/HEAD", ".git/refs", ".git/packed-refs"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
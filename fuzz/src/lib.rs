@@ -0,0 +1,48 @@
+//! Checks for the fuzz target, which covers every implemented day.
+//!
+//! The first byte of the fuzzed data picks the day, and the rest is its input.
+//!
+//! Any input may be rejected, but nothing may panic, including overflowing arithmetic in debug
+//! builds. The generator must cope with anything, but each part only has to solve input that
+//! passes the day's `validate` for that part, and may rely on what it checks.
+
+use aoc_2024::{find_day, list_days, Answer, Part, RunOptions, Solution};
+
+pub fn check(data: &[u8]) {
+    let Some((&selector, input)) = data.split_first() else {
+        return;
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return;
+    };
+    let days: Vec<_> = list_days().collect();
+    let (year, day) = days[usize::from(selector) % days.len()];
+    check_day(year, day, input);
+}
+
+fn check_day(year: u16, day: usize, input: &str) {
+    let solution = find_day(year, day).expect("listed days are implemented");
+    _ = (solution.generator)(input);
+    if let (Some(part1), Some(part2)) = (
+        checked_part(&solution, Part::One, input),
+        checked_part(&solution, Part::Two, input),
+    ) {
+        // The runner may solve both parts at once with `Day::solve`
+        let results = (solution.run)(input, &RunOptions::default())
+            .expect("the input was accepted before");
+        assert_eq!(results.part1, Some(part1));
+        assert_eq!(results.part2, Some(part2));
+    }
+}
+
+/// Solves `part`, or `None` if the input is malformed or breaks the day's assumptions
+fn checked_part(solution: &Solution, part: Part, input: &str) -> Option<Answer> {
+    if !(solution.validate)(input, Some(part)).is_empty() {
+        return None;
+    }
+    let solve = match part {
+        Part::One => solution.part1,
+        Part::Two => solution.part2,
+    };
+    solve(input).ok()
+}
@@ -0,0 +1,164 @@
+//! Per-thread allocation counting.
+//!
+//! Counting only happens when [`CountingAllocator`] is the global allocator, which the runner
+//! does when built with `--features count-allocs`. Counters are per thread, so concurrent days
+//! and parts don't see each other's allocations.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Allocations made during a single run of a phase
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, including reallocations
+    pub allocations: u64,
+    /// Total bytes requested
+    pub bytes: u64,
+    /// Most bytes live at once, above what was live when the phase started
+    pub peak_bytes: u64,
+}
+
+impl AllocStats {
+    /// What a phase that also does the work of `other` allocated on top of it.
+    ///
+    /// The peak is kept as is, since it isn't additive.
+    #[must_use]
+    pub fn without(self, other: Self) -> Self {
+        Self {
+            allocations: self.allocations.saturating_sub(other.allocations),
+            bytes: self.bytes.saturating_sub(other.bytes),
+            peak_bytes: self.peak_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    /// Signed: a thread can free memory allocated by another thread
+    live: i64,
+    peak: i64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            allocations: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+fn update(f: impl FnOnce(&mut Counters)) {
+    // Can only fail while the thread is being torn down, those allocations don't matter
+    _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        f(&mut c);
+        counters.set(c);
+    });
+}
+
+fn record_alloc(size: usize) {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    update(|c| {
+        c.allocations += 1;
+        c.bytes += size as u64;
+        c.live += size as i64;
+        c.peak = c.peak.max(c.live);
+    });
+}
+
+fn record_free(size: usize) {
+    update(|c| c.live -= size as i64);
+}
+
+/// Wraps the system allocator, counting every allocation on the current thread
+pub struct CountingAllocator;
+
+// SAFETY: Defers to `System` for the actual allocation, and only touches const-initialized
+// thread locals without destructors, which never allocate
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_free(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_free(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Whether [`CountingAllocator`] is the global allocator, otherwise all stats are zero
+#[must_use]
+pub fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Counts the allocations on this thread from [`Measurement::start`] to [`Measurement::finish`]
+pub struct Measurement(Counters);
+
+impl Measurement {
+    #[must_use]
+    pub fn start() -> Self {
+        let mut start = None;
+        update(|c| {
+            c.peak = c.live;
+            start = Some(*c);
+        });
+        Self(start.expect("only fails during thread teardown"))
+    }
+
+    #[must_use]
+    pub fn finish(self) -> AllocStats {
+        let end = COUNTERS.with(Cell::get);
+        AllocStats {
+            allocations: end.allocations - self.0.allocations,
+            bytes: end.bytes - self.0.bytes,
+            peak_bytes: (end.peak - self.0.live).max(0) as u64,
+        }
+    }
+}
+
+/// The peak resident set size of the process in bytes, from `/proc/self/status`
+#[must_use]
+pub fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kib: u64 = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+/// The answer to one part of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// Wide enough for any primitive integer a part returns
+    Int(i128),
+    Str(String),
+    /// The part has no solution yet
+    Unimplemented,
+}
+
+impl Answer {
+    #[must_use]
+    pub fn is_implemented(&self) -> bool {
+        !matches!(self, Self::Unimplemented)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Str(s) => f.write_str(s),
+            Self::Unimplemented => f.write_str("Unimplemented"),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(n: $int) -> Self {
+                    Self::Int(i128::from(n))
+                }
+            }
+        )*
+    };
+}
+
+from_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Self::Int(n as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Self::Int(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_owned())
+    }
+}
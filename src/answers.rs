@@ -0,0 +1,191 @@
+//! Expected answers, stored next to the puzzle input as `dayN.answers`.
+//!
+//! The file has one line per known part, either in plain text or hashed so real answers don't
+//! have to be committed:
+//!
+//! ```text
+//! # Comments and blank lines are ignored
+//! part1: 1928
+//! part2: fnv1a64:5c9f700b5c048efa
+//! ```
+//!
+//! Hashing only keeps answers from being read at a glance, it is trivial to brute force.
+//! Plain answers are compared as numbers when the actual answer is a number, so `042` matches 42.
+
+use crate::{Answer, Part};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Plain(String),
+    Hashed(u64),
+}
+
+impl Expected {
+    #[must_use]
+    pub fn hashed(answer: &str) -> Self {
+        Self::Hashed(fnv1a64(answer))
+    }
+
+    #[must_use]
+    pub fn matches(&self, actual: &Answer) -> bool {
+        match (self, actual) {
+            (_, Answer::Unimplemented) => false,
+            (Self::Plain(expected), Answer::Int(n)) => expected.parse::<i128>() == Ok(*n),
+            (Self::Plain(expected), Answer::Str(s)) => expected == s,
+            (Self::Hashed(hash), actual) => *hash == fnv1a64(&actual.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain(answer) => f.write_str(answer),
+            Self::Hashed(hash) => write!(f, "fnv1a64:{hash:016x}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<Expected>,
+    pub part2: Option<Expected>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: Expected,
+    },
+    Unknown,
+    /// The part has no solution yet, so there is nothing to check
+    Unimplemented,
+}
+
+impl Answers {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut result = Self::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |msg: &str| format!("line {}: {msg}", i + 1);
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| err("expected `partN: answer`"))?;
+            let value = value.trim();
+            let expected = match value.strip_prefix("fnv1a64:") {
+                Some(hash) => Expected::Hashed(
+                    u64::from_str_radix(hash, 16).map_err(|_| err("invalid hash"))?,
+                ),
+                None => Expected::Plain(value.to_owned()),
+            };
+            let slot = match key.trim() {
+                "part1" => &mut result.part1,
+                "part2" => &mut result.part2,
+                key => return Err(err(&format!("unknown key `{key}`"))),
+            };
+            if slot.replace(expected).is_some() {
+                return Err(err("duplicate answer"));
+            }
+        }
+        Ok(result)
+    }
+
+    #[must_use]
+    pub fn get(&self, part: Part) -> Option<&Expected> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+
+    #[must_use]
+    pub fn check(&self, part: Part, actual: &Answer) -> Verdict {
+        match self.get(part) {
+            _ if !actual.is_implemented() => Verdict::Unimplemented,
+            None => Verdict::Unknown,
+            Some(expected) if expected.matches(actual) => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(part1) = &self.part1 {
+            writeln!(f, "part1: {part1}")?;
+        }
+        if let Some(part2) = &self.part2 {
+            writeln!(f, "part2: {part2}")?;
+        }
+        Ok(())
+    }
+}
+
+fn fnv1a64(s: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    s.bytes().fold(OFFSET_BASIS, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(PRIME)
+    })
+}
+
+#[test]
+fn round_trip() {
+    let answers = Answers {
+        part1: Some(Expected::Plain("1928".to_owned())),
+        part2: Some(Expected::hashed("2858")),
+    };
+    let parsed = Answers::parse(&answers.to_string()).unwrap();
+    assert_eq!(parsed, answers);
+    assert_eq!(parsed.check(Part::One, &Answer::Int(1928)), Verdict::Pass);
+    assert_eq!(parsed.check(Part::Two, &Answer::Int(2858)), Verdict::Pass);
+    assert_eq!(
+        parsed.check(Part::Two, &Answer::Int(2859)),
+        Verdict::Fail {
+            expected: Expected::hashed("2858")
+        }
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Answers::parse("# comment\n\npart2: 5\n").unwrap(),
+        Answers {
+            part1: None,
+            part2: Some(Expected::Plain("5".to_owned())),
+        }
+    );
+    assert_eq!(
+        Answers::parse("part1: 4")
+            .unwrap()
+            .check(Part::Two, &Answer::Int(4)),
+        Verdict::Unknown
+    );
+    assert!(Answers::parse("part3: 4").is_err());
+    assert!(Answers::parse("part1 4").is_err());
+    assert!(Answers::parse("part1: 4\npart1: 5").is_err());
+    assert!(Answers::parse("part1: fnv1a64:xyz").is_err());
+}
+
+#[test]
+fn typed_answers() {
+    let answers = Answers::parse("part1: 042\npart2: abc").unwrap();
+    assert_eq!(answers.check(Part::One, &Answer::Int(42)), Verdict::Pass);
+    assert_eq!(
+        answers.check(Part::Two, &Answer::Str("abc".to_owned())),
+        Verdict::Pass
+    );
+    assert_eq!(
+        answers.check(Part::Two, &Answer::Unimplemented),
+        Verdict::Unimplemented
+    );
+}
@@ -0,0 +1,462 @@
+//! Saving bench results as CSV, and comparing them against saved baselines and time budgets.
+//!
+//! A baseline is the CSV written by `bench --save-baseline`, the same as `bench --format csv`, or
+//! by `cargo bench --bench parts -- --save-baseline`. Each row is one phase of a day, and the
+//! `mode` column keeps the two apart since the bench harness times parsing as part of each part.
+//!
+//! Both compare mean times: a regression is a significant difference in means by Welch's t-test,
+//! and a budget is a maximum mean. Medians are only for display.
+
+use crate::{Answer, RunnerError, Stats};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::time::Duration;
+
+/// Welch's t above this is considered significant. Bench samples aren't normally distributed,
+/// so this is deliberately stricter than the usual ~2.
+pub const SIGNIFICANT_T: f64 = 3.0;
+
+/// Where and how the results were produced
+pub struct Environment {
+    pub cpu: String,
+    pub rustc: &'static str,
+    pub git_head: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+}
+
+impl Environment {
+    #[must_use]
+    pub fn detect() -> Self {
+        Self {
+            cpu: cpu_model().unwrap_or_else(|| "unknown".to_owned()),
+            rustc: env!("AOC_RUSTC_VERSION"),
+            git_head: env!("AOC_GIT_HEAD"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+        }
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "model name").then(|| value.trim().to_owned())
+    })
+}
+
+/// The timings of one phase of a day, a row of the CSV
+#[derive(Debug, Copy, Clone)]
+pub struct Row<'a> {
+    /// The command that produced the row, e.g. `bench`
+    pub mode: &'a str,
+    pub year: u16,
+    pub day: usize,
+    /// `gen`, `part1` or `part2`
+    pub phase: &'a str,
+    /// `None` for the generator
+    pub answer: Option<&'a Answer>,
+    pub stats: Stats,
+}
+
+/// One row per phase, with the environment repeated on every row so each row stands alone
+#[must_use]
+pub fn csv<'a>(env: &Environment, rows: impl IntoIterator<Item = Row<'a>>) -> String {
+    let mut out = String::from(
+        "mode,year,day,phase,answer,samples,min_ns,median_ns,mean_ns,stddev_ns,cpu,rustc,git_head,os,arch\n",
+    );
+    for row in rows {
+        let stats = row.stats;
+        _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(row.mode),
+            row.year,
+            row.day,
+            row.phase,
+            csv_field(&row.answer.map_or(String::new(), ToString::to_string)),
+            stats.samples,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.mean.as_nanos(),
+            stats.stddev.as_nanos(),
+            csv_field(&env.cpu),
+            csv_field(env.rustc),
+            csv_field(env.git_head),
+            csv_field(env.os),
+            csv_field(env.arch),
+        );
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    Gen,
+    Part1,
+    Part2,
+    /// The sum of all phases
+    Total,
+}
+
+impl Phase {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Gen => "gen",
+            Self::Part1 => "part1",
+            Self::Part2 => "part2",
+            Self::Total => "total",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Gen, Self::Part1, Self::Part2, Self::Total]
+            .into_iter()
+            .find(|phase| phase.name() == name)
+    }
+}
+
+/// A maximum mean time for a phase of a day, e.g. `day6.part1<50us`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budget {
+    pub day: usize,
+    pub phase: Phase,
+    pub limit: Duration,
+}
+
+impl Budget {
+    /// Parses `dayN[.PHASE]<DURATION`, where the phase defaults to `total`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let err = || format!("invalid budget `{s}`, expected e.g. `day6.part1<50us`");
+        let (target, limit) = s.split_once('<').ok_or_else(err)?;
+        let (day, phase) = match target.trim().split_once('.') {
+            Some((day, phase)) => (day, Phase::from_name(phase).ok_or_else(err)?),
+            None => (target.trim(), Phase::Total),
+        };
+        let day = day
+            .strip_prefix("day")
+            .and_then(|day| day.parse().ok())
+            .ok_or_else(err)?;
+        let limit = parse_duration(limit.trim()).ok_or_else(err)?;
+        Ok(Self { day, phase, limit })
+    }
+}
+
+/// Parses a duration like `50us`, `1.5ms` or `2s`
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let unit_start = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, unit) = s.split_at(unit_start);
+    let value: f64 = value.parse().ok()?;
+    let nanos_per_unit = match unit {
+        "ns" => 1.0,
+        "us" | "µs" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        _ => return None,
+    };
+    Some(Duration::from_nanos((value * nanos_per_unit).round() as u64))
+}
+
+/// The rows of a saved CSV with a single mode
+#[derive(Debug, Default)]
+pub struct Baseline {
+    stats: HashMap<(u16, usize, String), Stats>,
+}
+
+impl Baseline {
+    /// Loads the rows of `mode` from the CSV at `path`, failing if there are none, e.g. because
+    /// the file was saved by a different command.
+    pub fn load(path: &str, mode: &str) -> Result<Self, RunnerError> {
+        let err = |message| RunnerError::BaselineFile {
+            path: path.to_owned(),
+            message,
+        };
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| err(format!("failed to read: {e}")))?;
+        let baseline = Self::parse(&contents, mode).map_err(err)?;
+        if baseline.stats.is_empty() {
+            return Err(err(format!("no rows with mode `{mode}`")));
+        }
+        Ok(baseline)
+    }
+
+    /// The stats saved for a phase of a day
+    #[must_use]
+    pub fn get(&self, year: u16, day: usize, phase: &str) -> Option<&Stats> {
+        self.stats.get(&(year, day, phase.to_owned()))
+    }
+
+    fn parse(s: &str, mode: &str) -> Result<Self, String> {
+        let mut lines = s.lines();
+        let header = split_csv_line(lines.next().ok_or("empty file")?);
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| format!("missing column `{name}`"))
+        };
+        let [mode_column, year, day, phase, samples, min, median, mean, stddev] = [
+            "mode",
+            "year",
+            "day",
+            "phase",
+            "samples",
+            "min_ns",
+            "median_ns",
+            "mean_ns",
+            "stddev_ns",
+        ]
+        .map(column);
+        let (mode_column, year, day, phase) = (mode_column?, year?, day?, phase?);
+        let (samples, min, median, mean, stddev) = (samples?, min?, median?, mean?, stddev?);
+
+        let mut result = Self::default();
+        for (i, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+            let fields = split_csv_line(line);
+            if fields.get(mode_column).map(String::as_str) != Some(mode) {
+                continue;
+            }
+            let field = |idx: usize| {
+                fields
+                    .get(idx)
+                    .and_then(|f| f.parse::<u64>().ok())
+                    .ok_or_else(|| format!("line {}: invalid or missing field", i + 2))
+            };
+            let nanos = |idx| field(idx).map(Duration::from_nanos);
+            let key = (
+                u16::try_from(field(year)?).map_err(|_| format!("line {}: invalid year", i + 2))?,
+                field(day)? as usize,
+                fields.get(phase).cloned().unwrap_or_default(),
+            );
+            let stats = Stats {
+                samples: field(samples)? as usize,
+                min: nanos(min)?,
+                median: nanos(median)?,
+                mean: nanos(mean)?,
+                stddev: nanos(stddev)?,
+            };
+            result.stats.insert(key, stats);
+        }
+        Ok(result)
+    }
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// How the mean time of a phase changed since the baseline
+#[derive(Debug, Copy, Clone)]
+pub struct Change {
+    pub percent: f64,
+    /// Welch's t, positive if it got slower. `None` if either has fewer than two samples
+    pub t: Option<f64>,
+}
+
+impl Change {
+    #[must_use]
+    pub fn between(old: &Stats, new: &Stats) -> Self {
+        let ratio = new.mean.as_nanos() as f64 / old.mean.as_nanos().max(1) as f64;
+        Self {
+            percent: (ratio - 1.0) * 100.0,
+            t: new.welch_t(old),
+        }
+    }
+
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.t.is_some_and(|t| t.abs() > SIGNIFICANT_T)
+    }
+
+    /// Significantly slower, by more than `threshold_percent`
+    #[must_use]
+    pub fn is_regression(&self, threshold_percent: f64) -> bool {
+        self.is_significant() && self.percent > threshold_percent
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match (self.is_significant(), self.percent > 0.0) {
+            (false, _) => "no change",
+            (true, true) => "slower",
+            (true, false) => "faster",
+        };
+        write!(f, "{:+.1}% ({verdict})", self.percent)
+    }
+}
+
+/// Compares the rows of `year` against the baseline and budgets, returning every violation.
+///
+/// A budget for a phase that wasn't run is a violation too, since it can't be checked.
+#[must_use]
+pub fn check(
+    year: u16,
+    rows: &[Row<'_>],
+    baseline: Option<&Baseline>,
+    budgets: &[Budget],
+    threshold_percent: f64,
+) -> Vec<RunnerError> {
+    let mut errors = Vec::new();
+
+    for row in rows {
+        let Some(old) = baseline.and_then(|b| b.get(year, row.day, row.phase)) else {
+            continue;
+        };
+        let change = Change::between(old, &row.stats);
+        if let Some(t) = change.t.filter(|_| change.is_regression(threshold_percent)) {
+            errors.push(RunnerError::PerformanceRegression {
+                year,
+                day: row.day,
+                detail: format!(
+                    "{} mean {:?} -> {:?} ({:+.1}%, t = {t:.1})",
+                    row.phase, old.mean, row.stats.mean, change.percent
+                ),
+            });
+        }
+    }
+
+    for budget in budgets {
+        let day_rows: Vec<&Row<'_>> = rows.iter().filter(|row| row.day == budget.day).collect();
+        let (phase, limit) = (budget.phase.name(), budget.limit);
+        let detail = match phase_mean(&day_rows, budget.phase) {
+            _ if day_rows.is_empty() => format!(
+                "the day was not benched, so its {phase} budget of {limit:?} could not be checked"
+            ),
+            Some(mean) if mean <= limit => continue,
+            Some(mean) => format!("{phase} mean {mean:?} > {limit:?}"),
+            None => {
+                format!("{phase} was not run, so its budget of {limit:?} could not be checked")
+            }
+        };
+        errors.push(RunnerError::OverBudget {
+            year,
+            day: budget.day,
+            detail,
+        });
+    }
+
+    errors
+}
+
+/// The mean time of `phase` from the rows of a day, or `None` if it wasn't run
+fn phase_mean(rows: &[&Row<'_>], phase: Phase) -> Option<Duration> {
+    let mean = |phase: Phase| {
+        rows.iter()
+            .find(|row| row.phase == phase.name())
+            .map(|row| row.stats.mean)
+    };
+    match phase {
+        Phase::Total => Some(mean(Phase::Gen)? + mean(Phase::Part1)? + mean(Phase::Part2)?),
+        phase => mean(phase),
+    }
+}
+
+#[test]
+fn budgets() {
+    assert_eq!(
+        Budget::parse("day6.part1<50us"),
+        Ok(Budget {
+            day: 6,
+            phase: Phase::Part1,
+            limit: Duration::from_micros(50),
+        })
+    );
+    assert_eq!(
+        Budget::parse("day9 < 1.5ms"),
+        Ok(Budget {
+            day: 9,
+            phase: Phase::Total,
+            limit: Duration::from_micros(1500),
+        })
+    );
+    assert!(Budget::parse("day6.part3<50us").is_err());
+    assert!(Budget::parse("6.part1<50us").is_err());
+    assert!(Budget::parse("day6<50").is_err());
+}
+
+#[test]
+fn baseline_round_trip() {
+    let answer = Answer::from(41_u64);
+    let row = |mode, stats| Row {
+        mode,
+        year: 2024,
+        day: 6,
+        phase: "part1",
+        answer: Some(&answer),
+        stats,
+    };
+    let stats = Stats::from_samples(&[10, 12, 16].map(Duration::from_nanos));
+    let env = Environment {
+        cpu: "Some \"CPU\", 8 cores".to_owned(),
+        ..Environment::detect()
+    };
+    let csv = csv(&env, [row("bench", stats), row("parts", Stats::default())]);
+    let baseline = Baseline::parse(&csv, "bench").unwrap();
+    assert_eq!(baseline.get(2024, 6, "part1"), Some(&stats));
+    assert_eq!(baseline.get(2024, 6, "part2"), None);
+    assert!(Baseline::parse(&csv, "run").unwrap().stats.is_empty());
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,\"b\""), r#""a,""b""""#);
+    assert_eq!(split_csv_line("a,\"b,\"\"c\"\"\",d"), ["a", "b,\"c\"", "d"]);
+}
+
+#[test]
+fn unchecked_budgets() {
+    let stats = Stats::from_samples(&[Duration::from_nanos(10)]);
+    let rows = ["gen", "part1"].map(|phase| Row {
+        mode: "bench",
+        year: 2024,
+        day: 1,
+        phase,
+        answer: None,
+        stats,
+    });
+    let budgets = ["day1.part1<1s", "day1.part2<1s", "day1<1s", "day2<1s"]
+        .map(|budget| Budget::parse(budget).unwrap());
+    let errors = check(2024, &rows, None, &budgets, 5.0);
+    let days: Vec<_> = errors
+        .iter()
+        .map(|e| match e {
+            RunnerError::OverBudget { day, .. } => *day,
+            _ => panic!("unexpected error {e}"),
+        })
+        .collect();
+    assert_eq!(days, [1, 1, 2]);
+}
+
+#[test]
+fn regressions() {
+    let old = Stats::from_samples(&[100, 101, 99, 100].map(Duration::from_nanos));
+    let slower = Stats::from_samples(&[120, 121, 119, 120].map(Duration::from_nanos));
+    assert!(Change::between(&old, &slower).is_regression(5.0));
+    assert!(!Change::between(&old, &slower).is_regression(25.0));
+    assert!(!Change::between(&slower, &old).is_regression(5.0));
+    assert_eq!(
+        Change::between(&slower, &old).to_string(),
+        "-16.7% (faster)"
+    );
+}
@@ -0,0 +1,516 @@
+use aoc_2024::baseline::{parse_duration, Budget};
+use aoc_2024::{InputSource, Part, RunOptions, DAYS_PER_YEAR};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: aoc-2024 [COMMAND] [OPTIONS] [DAYS]
+
+Commands:
+  run      Run the selected days, printing answers and timings (default)
+  list     List every day of each year and whether it has a solution
+  bench    Time each phase of the selected days over many runs, printing statistics
+  check    Run the selected days, comparing answers against dayN.answers
+  report   Bench the selected days and print a timing table, see --markdown
+  watch    Re-run each selected day whenever its input file changes
+  validate Check each selected input against the assumptions its solution makes, like maximum
+           sizes, reporting every violation. With --part, only that part's assumptions are
+           checked. Days with valid inputs are then run like `run`
+  gen-input
+           Print a random input for a single day, shaped like a real one, see --seed and --scale
+  help     Print this message
+
+Options:
+  -y, --year <YEAR>       Select the event to run [default: latest]
+  -d, --day <DAYS>        Select days, same as the DAYS argument
+  -p, --part <1|2>        Only run the given part
+  -n, --samples <N>       For `bench` and `report`, timed runs of each phase [default: 100]
+      --warmup <N>        For `bench` and `report`, untimed runs of each phase [default: 10]
+      --save-baseline <PATH>
+                          For `bench`, save the timings as a baseline to compare against later
+      --baseline <PATH>   For `bench`, fail if the mean time of a phase is significantly slower
+                          than the baseline, by Welch's t-test
+      --threshold <PCT>   With --baseline, ignore slowdowns smaller than PCT percent [default: 5]
+      --budget <BUDGET>   For `bench`, fail if a mean time exceeds the budget, e.g.
+                          `day6.part1<50us` or `day9<1ms` for the total. May be repeated. A budget
+                          for a phase or day that isn't benched fails too
+      --repeat <N>        For `run`, run each phase N times in a loop, e.g. for `perf record`
+  -j, --jobs <N>          Run up to N days at once. Timings get noisier [default: 1]
+      --parallel-parts    Run part 1 and part 2 of each day at the same time
+      --isolate           Run each day in a child process, so a crash only fails that day
+      --allocs            For `run` and `bench`, print heap allocations per phase and peak RSS.
+                          Requires building with `--features count-allocs`
+      --strict-allocs     Fail if a part marked as allocation free allocates. Implies --allocs
+      --timeout <TIME>    Give up on a day once a single run of a phase takes longer than TIME,
+                          e.g. `5s`, reporting it as TIMEOUT and moving on to the next day
+      --interval <TIME>   For `watch`, how often to check for changes [default: 500ms]
+  -i, --input <PATH>      Read the input for a single day from PATH, or stdin if PATH is `-`
+      --input-dir <DIR>   Read inputs from DIR/YEAR/dayN.txt [default: input]
+      --format <FORMAT>   For `run` and `bench`, output `text`, `json` or `csv` [default: text]
+      --markdown          For `report`, print a GitHub flavored markdown table (the default)
+      --record            For `check`, save the current answers as the expected answers
+      --hash              With --record, store hashes of the answers instead of plain text
+      --examples          For `check`, run the examples from the puzzle text instead of inputs
+      --seed <S>          For `gen-input`, the seed to generate the input from [default: 0]
+      --scale <K>         For `gen-input`, roughly how many times larger than a real input to
+                          make it, e.g. 0.01 for a tiny one, within the limits of the solution
+                          [default: 1]
+  -h, --help              Print this message
+
+DAYS is a comma separated list of days or inclusive ranges, e.g. `1-5,9`.
+If omitted, every implemented day is selected.
+
+Exit status:
+  0  Success
+  2  Invalid arguments
+  3  Missing or malformed puzzle input, or one that breaks a solution's assumptions
+  4  Unknown year or day
+  5  Selected day is not implemented, or has no input generator
+  6  A solution panicked or crashed
+  7  An answer did not match the expected answer
+  8  A phase regressed compared to the baseline
+  9  A phase exceeded its time budget, or its budget couldn't be checked
+ 10  An allocation free part allocated
+ 11  A phase timed out
+ 12  A baseline file could not be read or written
+ 13  An answers file could not be written";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run,
+    List,
+    Bench,
+    Check,
+    Report,
+    Watch,
+    Validate,
+    GenInput,
+    Help,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "run" => Self::Run,
+            "list" => Self::List,
+            "bench" => Self::Bench,
+            "check" => Self::Check,
+            "report" => Self::Report,
+            "watch" => Self::Watch,
+            "validate" => Self::Validate,
+            "gen-input" => Self::GenInput,
+            "help" => Self::Help,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone)]
+pub struct Args {
+    pub command: Command,
+    /// `None` if no year was specified
+    pub year: Option<u16>,
+    /// Sorted and deduplicated, `None` if no days were specified
+    pub days: Option<Vec<usize>>,
+    pub options: RunOptions,
+    pub input: InputSource,
+    /// Number of days to run at once
+    pub jobs: usize,
+    /// Run each day in its own child process
+    pub isolate: bool,
+    pub allocs: bool,
+    pub strict_allocs: bool,
+    /// How often `watch` polls the input files
+    pub interval: Duration,
+    pub format: Format,
+    pub baseline: Option<String>,
+    pub save_baseline: Option<String>,
+    /// Minimum slowdown in percent to report a regression
+    pub threshold: f64,
+    pub budgets: Vec<Budget>,
+    pub record: bool,
+    pub hash: bool,
+    /// Check the puzzle examples instead of the inputs
+    pub examples: bool,
+    /// Seed for `gen-input`
+    pub seed: u64,
+    /// Size of the generated input relative to a real one
+    pub scale: f64,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter().peekable();
+        let mut result = Self {
+            command: Command::Run,
+            year: None,
+            days: None,
+            options: RunOptions::default(),
+            input: InputSource::default(),
+            jobs: 1,
+            isolate: false,
+            allocs: false,
+            strict_allocs: false,
+            interval: Duration::from_millis(500),
+            format: Format::Text,
+            baseline: None,
+            save_baseline: None,
+            threshold: 5.0,
+            budgets: Vec::new(),
+            record: false,
+            hash: false,
+            examples: false,
+            seed: 0,
+            scale: 1.0,
+        };
+        let mut input_set = false;
+        let (mut samples, mut warmup, mut repeat) = (None, None, None);
+        let mut markdown = false;
+        let mut generates = false;
+
+        if let Some(command) = args.peek().and_then(|arg| Command::from_name(arg)) {
+            result.command = command;
+            args.next();
+        }
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = |name: &str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for {name}"))
+            };
+            match flag {
+                "-h" | "--help" => result.command = Command::Help,
+                "-y" | "--year" => {
+                    let year = value(flag)?;
+                    result.year = Some(year.parse().map_err(|_| format!("invalid year `{year}`"))?);
+                }
+                "-d" | "--day" => {
+                    if result.days.is_some() {
+                        return Err("days may only be given once".to_owned());
+                    }
+                    result.days = Some(parse_days(&value(flag)?)?);
+                }
+                "-p" | "--part" => {
+                    result.options.part = Some(parse_part(&value(flag)?)?);
+                }
+                "-n" | "--samples" => samples = Some(parse_count(flag, &value(flag)?, 1)?),
+                "--warmup" => warmup = Some(parse_count(flag, &value(flag)?, 0)?),
+                "--repeat" => repeat = Some(parse_count(flag, &value(flag)?, 1)?),
+                "--format" => {
+                    result.format = match value(flag)?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        "csv" => Format::Csv,
+                        format => return Err(format!("unknown format `{format}`")),
+                    };
+                }
+                "--baseline" => result.baseline = Some(value(flag)?),
+                "--save-baseline" => result.save_baseline = Some(value(flag)?),
+                "--threshold" => {
+                    let threshold = value(flag)?;
+                    result.threshold = threshold
+                        .parse()
+                        .ok()
+                        .filter(|t: &f64| *t >= 0.0)
+                        .ok_or_else(|| format!("invalid threshold `{threshold}`"))?;
+                }
+                "--budget" => result.budgets.push(Budget::parse(&value(flag)?)?),
+                "-j" | "--jobs" => result.jobs = parse_count(flag, &value(flag)?, 1)? as usize,
+                "--parallel-parts" => result.options.parallel_parts = true,
+                "--timeout" => {
+                    let timeout = value(flag)?;
+                    result.options.timeout = Some(
+                        parse_duration(&timeout)
+                            .filter(|timeout| !timeout.is_zero())
+                            .ok_or_else(|| format!("invalid timeout `{timeout}`"))?,
+                    );
+                }
+                "--interval" => {
+                    let interval = value(flag)?;
+                    result.interval = parse_duration(&interval)
+                        .filter(|interval| !interval.is_zero())
+                        .ok_or_else(|| format!("invalid interval `{interval}`"))?;
+                }
+                "--isolate" => result.isolate = true,
+                "--allocs" => result.allocs = true,
+                "--strict-allocs" => {
+                    result.allocs = true;
+                    result.strict_allocs = true;
+                }
+                "--markdown" => markdown = true,
+                "--record" => result.record = true,
+                "--hash" => result.hash = true,
+                "--examples" => result.examples = true,
+                "--seed" => {
+                    let seed = value(flag)?;
+                    result.seed = seed.parse().map_err(|_| format!("invalid seed `{seed}`"))?;
+                    generates = true;
+                }
+                "--scale" => {
+                    let scale = value(flag)?;
+                    result.scale = scale
+                        .parse()
+                        .ok()
+                        .filter(|k: &f64| k.is_finite() && *k > 0.0)
+                        .ok_or_else(|| format!("invalid scale `{scale}`"))?;
+                    generates = true;
+                }
+                "-i" | "--input" | "--input-dir" => {
+                    if input_set {
+                        return Err("only one of --input or --input-dir may be given".to_owned());
+                    }
+                    input_set = true;
+                    let path = value(flag)?;
+                    result.input = match (flag, path.as_str()) {
+                        ("--input-dir", _) => InputSource::Dir(PathBuf::from(path)),
+                        (_, "-") => InputSource::Stdin,
+                        _ => InputSource::File(PathBuf::from(path)),
+                    };
+                }
+                _ if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+                days => {
+                    if result.days.is_some() {
+                        return Err("days may only be given once".to_owned());
+                    }
+                    result.days = Some(parse_days(days)?);
+                }
+            }
+        }
+
+        match result.command {
+            Command::Bench | Command::Report if repeat.is_none() => {
+                result.options.samples = samples.unwrap_or(100);
+                result.options.warmup = warmup.unwrap_or(10);
+            }
+            Command::Run if samples.is_none() && warmup.is_none() => {
+                result.options.samples = repeat.unwrap_or(1);
+            }
+            _ if samples.is_some() || warmup.is_some() => {
+                return Err(
+                    "--samples and --warmup are only valid for `bench` and `report`".to_owned(),
+                );
+            }
+            _ if repeat.is_some() => return Err("--repeat is only valid for `run`".to_owned()),
+            _ => {}
+        }
+
+        if result.format != Format::Text && !matches!(result.command, Command::Run | Command::Bench)
+        {
+            return Err("--format is only valid for `run` and `bench`".to_owned());
+        }
+
+        let compares = result.baseline.is_some() || !result.budgets.is_empty();
+        if (compares || result.save_baseline.is_some()) && result.command != Command::Bench {
+            return Err("baselines and budgets are only valid for `bench`".to_owned());
+        }
+
+        if result.command == Command::Watch && result.input == InputSource::Stdin {
+            return Err("`watch` cannot read input from stdin".to_owned());
+        }
+        if result.record && result.input == InputSource::Stdin {
+            return Err("--record cannot save answers for input from stdin".to_owned());
+        }
+
+        if markdown && result.command != Command::Report {
+            return Err("--markdown is only valid for `report`".to_owned());
+        }
+
+        if result.hash && !result.record {
+            return Err("--hash requires --record".to_owned());
+        }
+
+        if result.examples && result.command != Command::Check {
+            return Err("--examples is only valid for `check`".to_owned());
+        }
+        if result.examples && (result.record || input_set) {
+            return Err("--examples cannot be combined with --record or an input".to_owned());
+        }
+
+        if generates && result.command != Command::GenInput {
+            return Err("--seed and --scale are only valid for `gen-input`".to_owned());
+        }
+        if result.command == Command::GenInput
+            && (input_set || result.days.as_ref().is_none_or(|days| days.len() != 1))
+        {
+            return Err("`gen-input` requires exactly one day and no input".to_owned());
+        }
+
+        if matches!(result.input, InputSource::File(_) | InputSource::Stdin)
+            && result.days.as_ref().is_none_or(|days| days.len() != 1)
+        {
+            return Err("--input requires exactly one day to be selected".to_owned());
+        }
+
+        Ok(result)
+    }
+}
+
+fn parse_count(flag: &str, s: &str, min: u32) -> Result<u32, String> {
+    match s.parse() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!(
+            "invalid value `{s}` for {flag}, expected at least {min}"
+        )),
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("invalid part `{s}`, expected 1 or 2")),
+    }
+}
+
+/// Parses a day list like `1-5,9` into a sorted, deduplicated list of days, each within an event
+pub fn parse_days(s: &str) -> Result<Vec<usize>, String> {
+    let parse_day = |day: &str| {
+        day.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|day| (1..=DAYS_PER_YEAR).contains(day))
+            .ok_or_else(|| format!("invalid day `{day}`, expected 1 to {DAYS_PER_YEAR}"))
+    };
+
+    let mut days = Vec::new();
+    for item in s.split(',') {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                if start > end {
+                    return Err(format!("invalid day range `{item}`"));
+                }
+                days.extend(start..=end);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
+
+#[test]
+fn day_list() {
+    assert_eq!(parse_days("1-5,9"), Ok(vec![1, 2, 3, 4, 5, 9]));
+    assert_eq!(parse_days("9,3,3-4"), Ok(vec![3, 4, 9]));
+    assert!(parse_days("5-1").is_err());
+    assert!(parse_days("1,,2").is_err());
+    assert!(parse_days("0").is_err());
+    assert!(parse_days("26").is_err());
+    assert!(parse_days("1-99999999999").is_err());
+}
+
+#[test]
+fn legacy_day_argument() {
+    let args = Args::parse(["6".to_owned()]).unwrap();
+    assert_eq!(args.command, Command::Run);
+    assert_eq!(args.days, Some(vec![6]));
+    assert_eq!(args.options.part, None);
+}
+
+#[test]
+fn subcommand_with_options() {
+    let args = Args::parse(
+        ["bench", "--part=2", "-n", "10", "1-3"]
+            .iter()
+            .map(|&s| s.to_owned()),
+    )
+    .unwrap();
+    assert_eq!(args.command, Command::Bench);
+    assert_eq!(args.days, Some(vec![1, 2, 3]));
+    assert_eq!(args.options.part, Some(Part::Two));
+    assert_eq!(args.options.samples, 10);
+    assert_eq!(args.options.warmup, 10);
+}
+
+#[test]
+fn input_sources() {
+    let parse = |args: &[&str]| Args::parse(args.iter().map(|&s| s.to_owned()));
+
+    assert_eq!(parse(&["5"]).unwrap().input, InputSource::default());
+    assert_eq!(parse(&["5", "-i", "-"]).unwrap().input, InputSource::Stdin);
+    assert_eq!(
+        parse(&["--input=test.txt", "5"]).unwrap().input,
+        InputSource::File(PathBuf::from("test.txt"))
+    );
+    assert_eq!(
+        parse(&["--input-dir", "inputs"]).unwrap().input,
+        InputSource::Dir(PathBuf::from("inputs"))
+    );
+    assert!(parse(&["-i", "-"]).is_err());
+    assert!(parse(&["1-2", "-i", "-"]).is_err());
+    assert!(parse(&["5", "-i", "-", "--input-dir", "inputs"]).is_err());
+    assert!(parse(&["check", "--examples", "5"]).unwrap().examples);
+    assert!(parse(&["run", "--examples"]).is_err());
+    assert!(parse(&["check", "--examples", "5", "-i", "-"]).is_err());
+    assert!(parse(&["check", "--record", "5", "-i", "-"]).is_err());
+}
+
+#[test]
+fn year_and_day() {
+    let args = Args::parse(
+        ["--year", "2023", "--day", "5"]
+            .iter()
+            .map(|&s| s.to_owned()),
+    )
+    .unwrap();
+    assert_eq!(args.year, Some(2023));
+    assert_eq!(args.days, Some(vec![5]));
+    assert!(Args::parse(["--day=5".to_owned(), "6".to_owned()]).is_err());
+}
+
+#[test]
+fn sampling_options() {
+    let parse = |args: &[&str]| Args::parse(args.iter().map(|&s| s.to_owned()));
+
+    let run = parse(&["run"]).unwrap();
+    assert_eq!((run.options.samples, run.options.warmup), (1, 0));
+    let repeat = parse(&["run", "--repeat", "1000"]).unwrap();
+    assert_eq!((repeat.options.samples, repeat.options.warmup), (1000, 0));
+    let bench = parse(&["bench", "--warmup=0", "-n", "5"]).unwrap();
+    assert_eq!((bench.options.samples, bench.options.warmup), (5, 0));
+
+    let timeout = parse(&["--timeout", "5s"]).unwrap();
+    assert_eq!(timeout.options.timeout, Some(Duration::from_secs(5)));
+
+    assert!(parse(&["bench", "-n", "0"]).is_err());
+    assert!(parse(&["--timeout", "0s"]).is_err());
+    assert!(parse(&["bench", "--repeat", "5"]).is_err());
+    assert!(parse(&["run", "--warmup", "5"]).is_err());
+    assert!(parse(&["check", "-n", "5"]).is_err());
+}
+
+#[test]
+fn input_generation() {
+    let parse = |args: &[&str]| Args::parse(args.iter().map(|&s| s.to_owned()));
+
+    let gen = parse(&["gen-input", "--day", "6", "--seed", "42", "--scale=3"]).unwrap();
+    assert_eq!(
+        (gen.command, gen.seed, gen.scale),
+        (Command::GenInput, 42, 3.0)
+    );
+    let defaults = parse(&["gen-input", "9"]).unwrap();
+    assert_eq!((defaults.seed, defaults.scale), (0, 1.0));
+    assert_eq!(
+        parse(&["gen-input", "9", "--scale", "0.01"]).unwrap().scale,
+        0.01
+    );
+    assert!(parse(&["gen-input"]).is_err());
+    assert!(parse(&["gen-input", "1-2"]).is_err());
+    assert!(parse(&["gen-input", "6", "--scale", "0"]).is_err());
+    assert!(parse(&["run", "6", "--seed", "1"]).is_err());
+}
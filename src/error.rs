@@ -0,0 +1,209 @@
+use crate::{ParseError, Part};
+use std::time::Duration;
+use std::{fmt, io};
+
+/// Everything that can go wrong when running a day.
+///
+/// This is also the error of the library's [`crate::solve`], so new variants for the runner may
+/// be added without a breaking change.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RunnerError {
+    /// The puzzle input could not be read
+    MissingInput { path: String, source: io::Error },
+    /// The puzzle input is malformed
+    InvalidInput {
+        year: u16,
+        day: usize,
+        error: ParseError,
+    },
+    /// The puzzle input breaks assumptions the solution makes, see [`crate::Day::validate`]
+    ViolatedAssumptions {
+        year: u16,
+        day: usize,
+        violations: Vec<ParseError>,
+    },
+    /// The expected answers file could not be parsed
+    InvalidAnswers { path: String, message: String },
+    /// The expected answers file could not be written by `check --record`
+    AnswersNotSaved { path: String, source: io::Error },
+    /// There are no solutions at all for the year
+    UnknownYear { year: u16 },
+    /// The day is outside the range of days for the year
+    UnknownDay { year: u16, day: usize },
+    /// The day exists, but has no solution yet
+    UnimplementedDay { year: u16, day: usize },
+    /// The solution can't generate inputs, see [`crate::Day::generate_input`]
+    NoInputGenerator { year: u16, day: usize },
+    /// The solution panicked.
+    ///
+    /// Only reported when panics unwind, with `panic = "abort"` the process aborts instead.
+    SolverFailure {
+        year: u16,
+        day: usize,
+        message: String,
+    },
+    /// At least one part did not match its expected answer
+    WrongAnswer { year: u16, day: usize },
+    /// A phase is significantly slower than in the saved baseline
+    PerformanceRegression {
+        year: u16,
+        day: usize,
+        detail: String,
+    },
+    /// A baseline file could not be read, parsed or written
+    BaselineFile { path: String, message: String },
+    /// A phase took longer than its time budget, or its budget couldn't be checked
+    OverBudget {
+        year: u16,
+        day: usize,
+        detail: String,
+    },
+    /// A part marked as allocation free allocated
+    UnexpectedAllocation {
+        year: u16,
+        day: usize,
+        part: Part,
+        allocations: u64,
+    },
+    /// The child process running the day with `--isolate` died without reporting results
+    Crashed {
+        year: u16,
+        day: usize,
+        /// How the child exited
+        status: String,
+        /// Everything the child wrote to stderr
+        stderr: String,
+    },
+    /// A single run of a phase took longer than the timeout
+    Timeout {
+        year: u16,
+        day: usize,
+        /// `None` for the generator
+        part: Option<Part>,
+        timeout: Duration,
+    },
+}
+
+impl RunnerError {
+    /// The process exit code used when this error ends a run.
+    ///
+    /// `1` is left for unexpected failures, and `2` for usage errors.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::MissingInput { .. }
+            | Self::InvalidInput { .. }
+            | Self::ViolatedAssumptions { .. }
+            | Self::InvalidAnswers { .. } => 3,
+            Self::UnknownYear { .. } | Self::UnknownDay { .. } => 4,
+            Self::UnimplementedDay { .. } | Self::NoInputGenerator { .. } => 5,
+            Self::SolverFailure { .. } | Self::Crashed { .. } => 6,
+            Self::WrongAnswer { .. } => 7,
+            Self::PerformanceRegression { .. } => 8,
+            Self::OverBudget { .. } => 9,
+            Self::UnexpectedAllocation { .. } => 10,
+            Self::Timeout { .. } => 11,
+            Self::BaselineFile { .. } => 12,
+            Self::AnswersNotSaved { .. } => 13,
+        }
+    }
+}
+
+impl fmt::Display for RunnerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingInput { path, source } => {
+                write!(f, "failed to read input {path}: {source}")
+            }
+            Self::InvalidInput { year, day, error } => {
+                write!(f, "invalid input for {year} day {day} at {error}")
+            }
+            Self::ViolatedAssumptions {
+                year,
+                day,
+                violations,
+            } => {
+                write!(f, "input for {year} day {day} breaks the solution's assumptions:")?;
+                for violation in violations {
+                    for line in violation.to_string().lines() {
+                        write!(f, "\n    {line}")?;
+                    }
+                }
+                Ok(())
+            }
+            Self::InvalidAnswers { path, message } => {
+                write!(f, "invalid answers file {path}: {message}")
+            }
+            Self::AnswersNotSaved { path, source } => {
+                write!(f, "failed to write answers file {path}: {source}")
+            }
+            Self::UnknownYear { year } => write!(f, "no solutions for {year}"),
+            Self::UnknownDay { year, day } => write!(f, "{year} has no day {day}"),
+            Self::UnimplementedDay { year, day } => {
+                write!(f, "{year} day {day} is not implemented")
+            }
+            Self::NoInputGenerator { year, day } => {
+                write!(f, "{year} day {day} has no input generator")
+            }
+            Self::SolverFailure { year, day, message } => {
+                write!(f, "{year} day {day} failed: {message}")
+            }
+            Self::Crashed {
+                year,
+                day,
+                status,
+                stderr,
+            } => {
+                write!(f, "{year} day {day} crashed ({status})")?;
+                for line in stderr.trim().lines() {
+                    write!(f, "\n    {line}")?;
+                }
+                Ok(())
+            }
+            Self::WrongAnswer { year, day } => {
+                write!(f, "{year} day {day} gave the wrong answer")
+            }
+            Self::PerformanceRegression { year, day, detail } => {
+                write!(f, "{year} day {day} regressed: {detail}")
+            }
+            Self::BaselineFile { path, message } => write!(f, "baseline {path}: {message}"),
+            Self::OverBudget { year, day, detail } => {
+                write!(f, "{year} day {day} failed its budget: {detail}")
+            }
+            Self::UnexpectedAllocation {
+                year,
+                day,
+                part,
+                allocations,
+            } => write!(
+                f,
+                "{year} day {day} part {part} should not allocate, but made {allocations} allocations"
+            ),
+            Self::Timeout {
+                year,
+                day,
+                part: Some(part),
+                timeout,
+            } => write!(f, "{year} day {day} part {part}: TIMEOUT after {timeout:?}"),
+            Self::Timeout {
+                year,
+                day,
+                part: None,
+                timeout,
+            } => write!(f, "{year} day {day} generator: TIMEOUT after {timeout:?}"),
+        }
+    }
+}
+
+impl std::error::Error for RunnerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MissingInput { source, .. } | Self::AnswersNotSaved { source, .. } => {
+                Some(source)
+            }
+            Self::InvalidInput { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
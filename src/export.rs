@@ -0,0 +1,199 @@
+//! Machine readable output of run results, for `--format json` and `--format csv`, and the
+//! markdown table for `report`
+
+use aoc_2024::answers::Verdict;
+use aoc_2024::baseline::{Environment, Row};
+use aoc_2024::{Answer, DayResults, Stats, Timing};
+use std::fmt::Write;
+use std::time::Duration;
+
+pub struct Record {
+    pub year: u16,
+    pub day: usize,
+    pub results: DayResults,
+}
+
+impl Record {
+    /// Each phase that was run, with its name and answer (`None` for the generator)
+    pub fn phases(&self) -> impl Iterator<Item = (&'static str, Stats, Option<&Answer>)> {
+        let stats = self.results.stats();
+        [
+            ("gen", stats.gen, None),
+            ("part1", stats.part1, self.results.part1.as_ref()),
+            ("part2", stats.part2, self.results.part2.as_ref()),
+        ]
+        .into_iter()
+        .filter(|&(_, stats, _)| stats.samples != 0)
+    }
+}
+
+pub fn json(mode: &str, env: &Environment, records: &[Record]) -> String {
+    let mut out = String::new();
+    out.push_str("{\n  \"environment\": {");
+    let env_fields = [
+        ("cpu", env.cpu.as_str()),
+        ("rustc", env.rustc),
+        ("git_head", env.git_head),
+        ("os", env.os),
+        ("arch", env.arch),
+    ];
+    for (i, (key, value)) in env_fields.into_iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        _ = write!(out, "{sep}\n    \"{key}\": {}", json_string(value));
+    }
+    _ = write!(
+        out,
+        "\n  }},\n  \"mode\": {},\n  \"days\": [",
+        json_string(mode)
+    );
+
+    for (i, record) in records.iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        // Numbers stay numbers, and parts without an answer are null
+        let answer = |answer: &Option<Answer>| match answer {
+            Some(Answer::Int(n)) => n.to_string(),
+            Some(Answer::Str(s)) => json_string(s),
+            Some(Answer::Unimplemented) | None => "null".to_owned(),
+        };
+        _ = write!(
+            out,
+            "{sep}\n    {{\n      \"year\": {},\n      \"day\": {},\n      \"part1\": {},\n      \"part2\": {},\n      \"phases\": {{",
+            record.year,
+            record.day,
+            answer(&record.results.part1),
+            answer(&record.results.part2),
+        );
+        for (j, (name, stats, _)) in record.phases().enumerate() {
+            let sep = if j == 0 { "" } else { "," };
+            _ = write!(
+                out,
+                "{sep}\n        \"{name}\": {{\"samples\": {}, \"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"stddev_ns\": {}}}",
+                stats.samples,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos(),
+            );
+        }
+        out.push_str("\n      }\n    }");
+    }
+    out.push_str("\n  ]\n}\n");
+    out
+}
+
+/// The rows for the CSV, one per phase of each day
+pub fn rows<'a>(mode: &'a str, records: &'a [Record]) -> impl Iterator<Item = Row<'a>> {
+    records.iter().flat_map(move |record| {
+        record.phases().map(move |(phase, stats, answer)| Row {
+            mode,
+            year: record.year,
+            day: record.day,
+            phase,
+            answer,
+            stats,
+        })
+    })
+}
+
+pub enum ReportRow<'a> {
+    Solved {
+        record: &'a Record,
+        /// For each part that was run
+        verdicts: Vec<Verdict>,
+    },
+    Unimplemented {
+        day: usize,
+    },
+    /// The day exists but could not be run, the error has already been reported
+    Failed {
+        day: usize,
+    },
+}
+
+/// A GitHub flavored markdown table of median timings
+pub fn markdown(rows: &[ReportRow<'_>]) -> String {
+    let mut totals = Timing::default();
+    for row in rows {
+        if let ReportRow::Solved { record, .. } = row {
+            totals.gen += record.results.timing.gen;
+            totals.part1 += record.results.timing.part1;
+            totals.part2 += record.results.timing.part2;
+        }
+    }
+    let total = totals.total();
+
+    let mut out = String::from(
+        "| Day | Generator | Part 1 | Part 2 | % of total | Answers |\n\
+         |----:|----------:|-------:|-------:|-----------:|:--------|\n",
+    );
+    for row in rows {
+        match row {
+            ReportRow::Solved { record, verdicts } => {
+                let timing = &record.results.timing;
+                let part = |answer: &Option<Answer>, time: Duration| match answer {
+                    None => "-".to_owned(),
+                    Some(Answer::Unimplemented) => "unimplemented".to_owned(),
+                    Some(_) => format!("{time:?}"),
+                };
+                let percent = if total.is_zero() {
+                    0.0
+                } else {
+                    timing.total().as_secs_f64() / total.as_secs_f64() * 100.0
+                };
+                let verdicts: Vec<_> = verdicts
+                    .iter()
+                    .map(|verdict| match verdict {
+                        Verdict::Pass => "pass",
+                        Verdict::Fail { .. } => "**FAIL**",
+                        Verdict::Unknown => "unknown",
+                        Verdict::Unimplemented => "unimplemented",
+                    })
+                    .collect();
+                _ = writeln!(
+                    out,
+                    "| {} | {:?} | {} | {} | {percent:.1}% | {} |",
+                    record.day,
+                    timing.gen,
+                    part(&record.results.part1, timing.part1),
+                    part(&record.results.part2, timing.part2),
+                    verdicts.join(" / "),
+                );
+            }
+            ReportRow::Unimplemented { day } => {
+                _ = writeln!(out, "| {day} | | | | | not implemented |");
+            }
+            ReportRow::Failed { day } => {
+                _ = writeln!(out, "| {day} | | | | | failed |");
+            }
+        }
+    }
+    _ = writeln!(
+        out,
+        "| **Total** | {:?} | {:?} | {:?} | {total:?} | |",
+        totals.gen, totals.part1, totals.part2
+    );
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => _ = write!(out, "\\u{:04x}", u32::from(c)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(json_string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+}
@@ -0,0 +1,421 @@
+//! Running each day in a child process, so a crash only fails that day.
+//!
+//! The child is this same binary, started with [`CHILD_ENV`] set. The parent writes a header line
+//! with the day and run options to the child's stdin, followed by the puzzle input. The child
+//! writes its results to stdout, one field per line:
+//!
+//! ```text
+//! part1 int 41
+//! samples gen 8163
+//! samples part1 3765
+//! allocs part1 0 0 0
+//! allocation_free 1 2
+//! ```
+//!
+//! or a single `invalid_input OFFSET MESSAGE`, `panic MESSAGE` or `timeout PHASE NANOS` line if
+//! the day failed without crashing.
+
+use aoc_2024::allocs::AllocStats;
+use aoc_2024::{
+    find_day, try_run, Answer, DayResults, ParseError, Part, RunOptions, RunnerError, Stats, Timing,
+};
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::process::{self, ExitCode, Stdio};
+use std::time::Duration;
+use std::{env, thread};
+
+const CHILD_ENV: &str = "AOC_2024_ISOLATED_CHILD";
+
+/// Whether this process was started by [`solve_day`] to run a single day
+pub fn is_child() -> bool {
+    env::var_os(CHILD_ENV).is_some()
+}
+
+/// Runs the day in a child process, turning a crash into a [`RunnerError::Crashed`]
+pub fn solve_day(
+    year: u16,
+    day: usize,
+    input: &str,
+    options: &RunOptions,
+) -> Result<DayResults, RunnerError> {
+    let crashed = |status: String, stderr: String| RunnerError::Crashed {
+        year,
+        day,
+        status,
+        stderr,
+    };
+    let exe = env::current_exe().map_err(|e| crashed(e.to_string(), String::new()))?;
+    let mut child = process::Command::new(exe)
+        .env(CHILD_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| crashed(format!("failed to start: {e}"), String::new()))?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let header = write_header(year, day, options);
+    let child_input = input.to_owned();
+    // Written from another thread, so a child filling its stdout or stderr can't deadlock us
+    let writer = thread::spawn(move || {
+        // If the child dies early the write fails, and the crash is reported below
+        let _ = stdin
+            .write_all(header.as_bytes())
+            .and_then(|()| stdin.write_all(child_input.as_bytes()));
+    });
+    let output = child
+        .wait_with_output()
+        .map_err(|e| crashed(e.to_string(), String::new()))?;
+    writer.join().expect("writing to the child panicked");
+
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match output
+        .status
+        .success()
+        .then(|| read_results(year, day, input, &stdout))
+    {
+        Some(Ok(result)) => {
+            // Keep what the day printed, like panic messages, as if it had run in this process
+            eprint!("{stderr}");
+            result
+        }
+        Some(Err(message)) => Err(crashed(format!("invalid output: {message}"), stderr)),
+        None => Err(crashed(output.status.to_string(), stderr)),
+    }
+}
+
+/// The entry point of the child process
+pub fn child_main() -> ExitCode {
+    let mut stdin = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut stdin) {
+        eprintln!("error: failed to read from the parent: {e}");
+        return ExitCode::from(2);
+    }
+    let Some(((year, day, options), input)) = stdin
+        .split_once('\n')
+        .and_then(|(header, input)| Some((read_header(header)?, input)))
+    else {
+        eprintln!("error: invalid header from the parent");
+        return ExitCode::from(2);
+    };
+
+    let result =
+        find_day(year, day).and_then(|solution| try_run(year, day, solution.run, input, &options));
+    let out = match result {
+        Ok(results) => write_results(&results),
+        Err(RunnerError::InvalidInput { error, .. }) => {
+            format!(
+                "invalid_input {} {}\n",
+                error.offset,
+                escape(&error.message)
+            )
+        }
+        Err(RunnerError::SolverFailure { message, .. }) => format!("panic {}\n", escape(&message)),
+        Err(RunnerError::Timeout { part, timeout, .. }) => {
+            format!("timeout {} {}\n", phase_name(part), timeout.as_nanos())
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+    print!("{out}");
+    ExitCode::SUCCESS
+}
+
+fn write_header(year: u16, day: usize, options: &RunOptions) -> String {
+    let part = options
+        .part
+        .map_or("both".to_owned(), |part| part.to_string());
+    let timeout = options
+        .timeout
+        .map_or("none".to_owned(), |timeout| timeout.as_nanos().to_string());
+    format!(
+        "{year} {day} {part} {} {} {} {timeout}\n",
+        options.warmup,
+        options.samples,
+        u8::from(options.parallel_parts),
+    )
+}
+
+fn read_header(header: &str) -> Option<(u16, usize, RunOptions)> {
+    let fields: Vec<_> = header.split(' ').collect();
+    let &[year, day, part, warmup, samples, parallel_parts, timeout] = fields.as_slice() else {
+        return None;
+    };
+    let options = RunOptions {
+        part: match part {
+            "both" => None,
+            part => Some(parse_part(part)?),
+        },
+        warmup: warmup.parse().ok()?,
+        samples: samples.parse().ok()?,
+        parallel_parts: parallel_parts == "1",
+        timeout: match timeout {
+            "none" => None,
+            nanos => Some(Duration::from_nanos(nanos.parse().ok()?)),
+        },
+    };
+    Some((year.parse().ok()?, day.parse().ok()?, options))
+}
+
+fn write_results(results: &DayResults) -> String {
+    let mut out = String::new();
+    for (name, answer) in [("part1", &results.part1), ("part2", &results.part2)] {
+        match answer {
+            Some(Answer::Int(n)) => writeln!(out, "{name} int {n}").unwrap(),
+            Some(Answer::Str(s)) => writeln!(out, "{name} str {}", escape(s)).unwrap(),
+            Some(Answer::Unimplemented) => writeln!(out, "{name} unimplemented").unwrap(),
+            None => {}
+        }
+    }
+    for (phase, samples, allocs) in [
+        (None, &results.samples.gen, &results.allocs.gen),
+        (
+            Some(Part::One),
+            &results.samples.part1,
+            &results.allocs.part1,
+        ),
+        (
+            Some(Part::Two),
+            &results.samples.part2,
+            &results.allocs.part2,
+        ),
+    ] {
+        let name = phase_name(phase);
+        write!(out, "samples {name}").unwrap();
+        for sample in samples {
+            write!(out, " {}", sample.as_nanos()).unwrap();
+        }
+        let AllocStats {
+            allocations,
+            bytes,
+            peak_bytes,
+        } = allocs;
+        writeln!(out, "\nallocs {name} {allocations} {bytes} {peak_bytes}").unwrap();
+    }
+    write!(out, "allocation_free").unwrap();
+    for part in results.allocation_free {
+        write!(out, " {part}").unwrap();
+    }
+    out.push('\n');
+    out
+}
+
+/// Parses the output of a child, `Err` if the output itself is malformed
+fn read_results(
+    year: u16,
+    day: usize,
+    input: &str,
+    output: &str,
+) -> Result<Result<DayResults, RunnerError>, String> {
+    let mut samples = Timing::<Vec<Duration>>::default();
+    let mut allocs = Timing::<AllocStats>::default();
+    let mut allocation_free: &'static [Part] = &[];
+    let (mut part1, mut part2) = (None, None);
+
+    for line in output.lines() {
+        let err = || format!("unexpected line `{line}`");
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let mut fields = value.split(' ').filter(|field| !field.is_empty());
+        let numbers = |fields: &mut dyn Iterator<Item = &str>| {
+            fields
+                .map(str::parse)
+                .collect::<Result<Vec<u64>, _>>()
+                .map_err(|_| err())
+        };
+        match key {
+            "part1" | "part2" => {
+                let answer = match value.split_once(' ').unwrap_or((value, "")) {
+                    ("int", n) => Answer::Int(n.parse().map_err(|_| err())?),
+                    ("str", s) => Answer::Str(unescape(s)),
+                    ("unimplemented", "") => Answer::Unimplemented,
+                    _ => return Err(err()),
+                };
+                if key == "part1" {
+                    part1 = Some(answer);
+                } else {
+                    part2 = Some(answer);
+                }
+            }
+            "samples" | "allocs" => {
+                let phase = fields.next().and_then(read_phase).ok_or_else(err)?;
+                let numbers = numbers(&mut fields)?;
+                if key == "samples" {
+                    *phase.of(&mut samples) =
+                        numbers.into_iter().map(Duration::from_nanos).collect();
+                } else {
+                    let &[allocations, bytes, peak_bytes] = numbers.as_slice() else {
+                        return Err(err());
+                    };
+                    *phase.of(&mut allocs) = AllocStats {
+                        allocations,
+                        bytes,
+                        peak_bytes,
+                    };
+                }
+            }
+            "allocation_free" => {
+                let parts: Vec<_> = fields
+                    .map(parse_part)
+                    .collect::<Option<_>>()
+                    .ok_or_else(err)?;
+                allocation_free = match parts.as_slice() {
+                    [] => &[],
+                    [Part::One] => &[Part::One],
+                    [Part::Two] => &[Part::Two],
+                    _ => &[Part::One, Part::Two],
+                };
+            }
+            "invalid_input" => {
+                let (offset, message) = value.split_once(' ').ok_or_else(err)?;
+                let offset = offset.parse().map_err(|_| err())?;
+                return Ok(Err(RunnerError::InvalidInput {
+                    year,
+                    day,
+                    error: ParseError::new(input, offset, unescape(message)),
+                }));
+            }
+            "panic" => {
+                return Ok(Err(RunnerError::SolverFailure {
+                    year,
+                    day,
+                    message: unescape(value),
+                }))
+            }
+            "timeout" => {
+                let phase = fields.next().and_then(read_phase).ok_or_else(err)?;
+                let &[nanos] = numbers(&mut fields)?.as_slice() else {
+                    return Err(err());
+                };
+                return Ok(Err(RunnerError::Timeout {
+                    year,
+                    day,
+                    part: phase.0,
+                    timeout: Duration::from_nanos(nanos),
+                }));
+            }
+            _ => return Err(err()),
+        }
+    }
+
+    if samples.gen.is_empty() {
+        return Err("missing results".to_owned());
+    }
+    Ok(Ok(DayResults {
+        timing: samples.map(|samples| Stats::from_samples(samples).median),
+        samples,
+        allocs,
+        allocation_free,
+        part1,
+        part2,
+    }))
+}
+
+/// A phase of a day, with `None` for the generator
+#[derive(Copy, Clone)]
+struct Phase(Option<Part>);
+
+impl Phase {
+    fn of<T>(self, timing: &mut Timing<T>) -> &mut T {
+        match self.0 {
+            None => &mut timing.gen,
+            Some(Part::One) => &mut timing.part1,
+            Some(Part::Two) => &mut timing.part2,
+        }
+    }
+}
+
+fn phase_name(part: Option<Part>) -> &'static str {
+    match part {
+        None => "gen",
+        Some(Part::One) => "part1",
+        Some(Part::Two) => "part2",
+    }
+}
+
+fn read_phase(name: &str) -> Option<Phase> {
+    [None, Some(Part::One), Some(Part::Two)]
+        .into_iter()
+        .find(|&part| phase_name(part) == name)
+        .map(Phase)
+}
+
+fn parse_part(s: &str) -> Option<Part> {
+    match s {
+        "1" => Some(Part::One),
+        "2" => Some(Part::Two),
+        _ => None,
+    }
+}
+
+/// Keeps answers and panic messages on a single line
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                result.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                result.push('\\');
+            }
+            (c, _) => result.push(c),
+        }
+    }
+    result
+}
+
+#[test]
+fn results_round_trip() {
+    let results = DayResults {
+        timing: Timing::default(),
+        samples: Timing {
+            gen: vec![Duration::from_nanos(5)],
+            part1: [3, 1, 2].map(Duration::from_nanos).to_vec(),
+            part2: vec![],
+        },
+        allocs: Timing {
+            gen: AllocStats {
+                allocations: 1,
+                bytes: 16,
+                peak_bytes: 16,
+            },
+            ..Timing::default()
+        },
+        allocation_free: &[Part::One, Part::Two],
+        part1: Some(Answer::Str("multi\nline \\n".to_owned())),
+        part2: Some(Answer::Int(-5)),
+    };
+    let parsed = read_results(2024, 1, "", &write_results(&results))
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed.samples.part1, results.samples.part1);
+    assert_eq!(parsed.timing.part1, Duration::from_nanos(2));
+    assert_eq!(parsed.allocs.gen, results.allocs.gen);
+    assert_eq!(parsed.allocation_free, results.allocation_free);
+    assert_eq!(parsed.part1, results.part1);
+    assert_eq!(parsed.part2, results.part2);
+
+    let options = RunOptions {
+        part: Some(Part::Two),
+        samples: 3,
+        timeout: Some(Duration::from_secs(1)),
+        ..RunOptions::default()
+    };
+    let header = write_header(2024, 6, &options);
+    let (year, day, parsed) = read_header(header.trim_end()).unwrap();
+    assert_eq!((year, day), (2024, 6));
+    assert_eq!(parsed.part, options.part);
+    assert_eq!(parsed.samples, 3);
+    assert_eq!(parsed.timeout, options.timeout);
+}
@@ -1,140 +1,850 @@
 extern crate core;
 
-use std::fmt;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{fmt, fs, io, panic};
 
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day9;
+pub mod allocs;
+mod answer;
+pub mod answers;
+pub mod baseline;
+mod error;
+mod parse;
+mod rng;
+mod stats;
+mod watchdog;
+pub mod year2024;
+
+use allocs::AllocStats;
+pub use answer::Answer;
+use answers::{Answers, Expected};
+pub use error::RunnerError;
+use parse::with_final_newline;
+pub use parse::ParseError;
+pub use rng::Rng;
+pub use stats::Stats;
+
+// The benchmark service expects `crate::dayN::{part1, part2}` for the current event
+pub use year2024::modules::*;
 
 pub trait Day {
-    type Parsed<'a>: Clone;
+    type Parsed<'a>: Clone + Send + Sync;
+
+    fn generator(input: &str) -> Result<Self::Parsed<'_>, ParseError>;
+
+    /// Parts which must not allocate, checked by the runner with `--strict-allocs`
+    const ALLOCATION_FREE: &'static [Part] = &[];
+
+    /// Examples from the puzzle text, checked by the tests and `check --examples`
+    const EXAMPLES: &'static [Example] = &[];
 
-    fn generator(input: &str) -> Self::Parsed<'_>;
+    /// Whether [`Day::solve`] reuses work from part 1 for part 2, so the runner should use it
+    const SHARES_WORK: bool = false;
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer>;
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer>;
+
+    /// Checks the input against the assumptions the solution makes, like maximum sizes, returning
+    /// every violation.
+    ///
+    /// `part` is the part that will be solved, or `None` for both, so assumptions only the other
+    /// part makes are skipped. Runs before the generator, so it can't assume the input is
+    /// well-formed either.
+    fn validate(_input: &str, _part: Option<Part>) -> Vec<ParseError> {
+        Vec::new()
+    }
+
+    /// Generates a random input shaped like a real one, for `gen-input`.
+    ///
+    /// `scale` is roughly how many times larger than a real input to make it, e.g. `0.01` for a
+    /// tiny one for tests, though it never breaks the assumptions checked by [`Day::validate`].
+    /// `None` if the day has no generator.
+    fn generate_input(_rng: &mut Rng, _scale: f64) -> Option<String> {
+        None
+    }
 
-    fn part1(input: Self::Parsed<'_>) -> impl fmt::Display;
-    fn part2(input: Self::Parsed<'_>) -> impl fmt::Display;
+    /// Solves both parts at once, for days where part 2 can reuse work from part 1.
+    ///
+    /// Runs the parts separately by default. With [`Day::SHARES_WORK`] set, the runner takes the
+    /// part 2 answer from here, and reports part 2 as the extra time and allocations this takes
+    /// over `part1` alone.
+    fn solve(input: Self::Parsed<'_>) -> (impl Into<Answer>, impl Into<Answer>) {
+        (Self::part1(input.clone()), Self::part2(input))
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct Timing {
-    pub gen: std::time::Duration,
-    pub part1: std::time::Duration,
-    pub part2: std::time::Duration,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::One => f.write_str("1"),
+            Self::Two => f.write_str("2"),
+        }
+    }
+}
+
+/// An example input from the puzzle text, with the answers the puzzle gives for it
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    /// `None` if the puzzle uses a different example for the part
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    #[must_use]
+    pub fn answer(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+
+    /// The answers to check the results against
+    #[must_use]
+    pub fn answers(&self) -> Answers {
+        let expected =
+            |answer: Option<&str>| answer.map(|answer| Expected::Plain(answer.to_owned()));
+        Answers {
+            part1: expected(self.part1),
+            part2: expected(self.part2),
+        }
+    }
+
+    /// Options to run only the parts with a known answer
+    #[must_use]
+    pub fn run_options(&self) -> RunOptions {
+        let part = match (self.part1, self.part2) {
+            (Some(_), None) => Some(Part::One),
+            (None, Some(_)) => Some(Part::Two),
+            _ => None,
+        };
+        RunOptions {
+            part,
+            ..RunOptions::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Only run this part, or both parts if `None`
+    pub part: Option<Part>,
+    /// Untimed runs of each phase before sampling
+    pub warmup: u32,
+    /// Timed runs of each phase, `0` is treated as `1`
+    pub samples: u32,
+    /// Run part 1 and part 2 on separate threads at the same time
+    pub parallel_parts: bool,
+    /// Give up on a day once a single run of one of its phases takes longer than this
+    pub timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    #[must_use]
+    pub fn runs(&self, part: Part) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Timing<T = Duration> {
+    pub gen: T,
+    pub part1: T,
+    pub part2: T,
+}
+
+impl Timing {
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.gen + self.part1 + self.part2
+    }
+}
+
+impl<T> Timing<T> {
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Timing<U> {
+        Timing {
+            gen: f(&self.gen),
+            part1: f(&self.part1),
+            part2: f(&self.part2),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct DayResults {
+    /// The median of `samples` for each phase
     pub timing: Timing,
-    pub part1: String,
-    pub part2: String,
+    /// Every timed run of each phase, empty for a part that was not run
+    pub samples: Timing<Vec<Duration>>,
+    /// Allocations made by the last run of each phase, see [`allocs`]
+    pub allocs: Timing<AllocStats>,
+    /// Parts the day promises not to allocate in
+    pub allocation_free: &'static [Part],
+    /// `None` if the part was not run
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+impl DayResults {
+    #[must_use]
+    pub fn stats(&self) -> Timing<Stats> {
+        self.samples.map(|samples| Stats::from_samples(samples))
+    }
 }
 
 impl fmt::Display for DayResults {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Gen: ({:?})\nPart 1: {} ({:?})\nPart 2: {} ({:?})",
-            self.timing.gen, self.part1, self.timing.part1, self.part2, self.timing.part2
-        )
+        write!(f, "Gen: ({:?})", self.timing.gen)?;
+        if let Some(part1) = &self.part1 {
+            write!(f, "\nPart 1: {} ({:?})", part1, self.timing.part1)?;
+        }
+        if let Some(part2) = &self.part2 {
+            write!(f, "\nPart 2: {} ({:?})", part2, self.timing.part2)?;
+        }
+        Ok(())
     }
 }
 
+/// `count` scaled for [`Day::generate_input`], rounded and at least one.
+fn scaled(count: usize, scale: f64) -> usize {
+    ((count as f64 * scale).round() as usize).max(1)
+}
+
 #[inline]
-fn time<O>(f: impl FnOnce() -> O) -> (O, std::time::Duration) {
+fn time<O>(f: impl FnOnce() -> O) -> (O, Duration) {
     let start = std::time::Instant::now();
     let res = f();
     let elapsed = start.elapsed();
     (res, elapsed)
 }
 
-#[must_use]
-pub fn run_day<D: Day>(input: &str) -> DayResults {
-    let (parsed, gen_time) = time(|| D::generator(input));
-
-    let (part1, part1_time) = time(|| D::part1(parsed.clone()));
-    let part1_str = part1.to_string();
-    drop(part1);
-
-    let (part2, part2_time) = time(|| D::part2(parsed));
-    let part2_str = part2.to_string();
-    drop(part2);
-
-    DayResults {
-        timing: Timing {
-            gen: gen_time,
-            part1: part1_time,
-            part2: part2_time,
-        },
+/// Runs `routine` on a fresh value from `setup` for every warmup and sample run.
+///
+/// Only `routine` is timed, and only the output and allocations of the last run are returned.
+/// `phase` is the part being run, or `None` for the generator.
+fn sample<I, O>(
+    options: &RunOptions,
+    phase: Option<Part>,
+    mut setup: impl FnMut() -> I,
+    mut routine: impl FnMut(I) -> O,
+) -> (O, Vec<Duration>, AllocStats) {
+    let samples = options.samples.max(1);
+    let mut times = Vec::with_capacity(samples as usize);
+    let mut output = None;
+    let mut allocs = AllocStats::default();
+    for i in 0..options.warmup + samples {
+        let input = setup();
+        watchdog::start(phase);
+        let measurement = allocs::Measurement::start();
+        let (out, elapsed) = time(|| black_box(routine(black_box(input))));
+        allocs = measurement.finish();
+        if i >= options.warmup {
+            times.push(elapsed);
+        }
+        output = Some(out);
+    }
+    watchdog::finish(phase);
+    (output.expect("at least one sample"), times, allocs)
+}
+
+pub fn run_day<D: Day>(input: &str, options: &RunOptions) -> Result<DayResults, ParseError> {
+    let mut samples = Timing::<Vec<Duration>>::default();
+    let mut allocs = Timing::<AllocStats>::default();
+
+    let parsed;
+    (parsed, samples.gen, allocs.gen) = sample(options, None, || input, D::generator);
+    let parsed = parsed?;
+
+    let run_part1 = || {
+        options.runs(Part::One).then(|| {
+            let (part1, times, allocs) =
+                sample(options, Some(Part::One), || parsed.clone(), D::part1);
+            (part1.into(), times, allocs)
+        })
+    };
+    let run_part2 = || {
+        options.runs(Part::Two).then(|| {
+            if D::SHARES_WORK {
+                let ((_, part2), times, allocs) =
+                    sample(options, Some(Part::Two), || parsed.clone(), D::solve);
+                (part2.into(), times, allocs)
+            } else {
+                let (part2, times, allocs) =
+                    sample(options, Some(Part::Two), || parsed.clone(), D::part2);
+                (part2.into(), times, allocs)
+            }
+        })
+    };
+
+    let (part1, part2) = if options.parallel_parts {
+        std::thread::scope(|s| {
+            let progress = watchdog::current();
+            let part1 = s.spawn(move || {
+                watchdog::set_current(progress);
+                run_part1()
+            });
+            let part2 = run_part2();
+            let part1 = part1.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            (part1, part2)
+        })
+    } else {
+        (run_part1(), run_part2())
+    };
+
+    let part1_str = part1.map(|(answer, times, part_allocs)| {
+        (samples.part1, allocs.part1) = (times, part_allocs);
+        answer
+    });
+    let part2_str = part2.map(|(answer, times, part_allocs)| {
+        (samples.part2, allocs.part2) = (times, part_allocs);
+        answer
+    });
+
+    if D::SHARES_WORK && part2_str.is_some() {
+        // Part 2 only costs what solving both parts takes on top of part 1, which is sampled
+        // here if it wasn't run, so part 2 is reported the same either way
+        let (part1_median, part1_allocs) = if part1_str.is_some() {
+            (Stats::from_samples(&samples.part1).median, allocs.part1)
+        } else {
+            let (_, times, part_allocs) =
+                sample(options, Some(Part::One), || parsed.clone(), D::part1);
+            (Stats::from_samples(&times).median, part_allocs)
+        };
+        for sample in &mut samples.part2 {
+            *sample = sample.saturating_sub(part1_median);
+        }
+        allocs.part2 = allocs.part2.without(part1_allocs);
+    }
+
+    Ok(DayResults {
+        timing: samples.map(|samples| Stats::from_samples(samples).median),
+        samples,
+        allocs,
+        allocation_free: D::ALLOCATION_FREE,
         part1: part1_str,
         part2: part2_str,
+    })
+}
+
+pub type DayFn = fn(&str, &RunOptions) -> Result<DayResults, ParseError>;
+
+/// A registered solution, with the [`Day`] methods the runner calls without a type
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub run: DayFn,
+    /// Only checks that the input parses, see [`Day::generator`]
+    pub generator: fn(&str) -> Result<(), ParseError>,
+    /// Parses and solves a single part, without timing it or using [`Day::solve`]
+    pub part1: fn(&str) -> Result<Answer, ParseError>,
+    pub part2: fn(&str) -> Result<Answer, ParseError>,
+    /// See [`Day::validate`]
+    pub validate: fn(&str, Option<Part>) -> Vec<ParseError>,
+    /// See [`Day::generate_input`]
+    pub generate_input: fn(&mut Rng, f64) -> Option<String>,
+    pub examples: &'static [Example],
+}
+
+impl Solution {
+    #[must_use]
+    pub const fn new<D: Day>() -> Self {
+        Self {
+            run: run_day::<D>,
+            generator: |input| D::generator(input).map(drop),
+            part1: |input| D::generator(input).map(|parsed| D::part1(parsed).into()),
+            part2: |input| D::generator(input).map(|parsed| D::part2(parsed).into()),
+            validate: D::validate,
+            generate_input: D::generate_input,
+            examples: D::EXAMPLES,
+        }
     }
 }
 
-pub const DAYS: &[fn(&str) -> DayResults] = &[
-    run_day::<day1::Day1>,
-    run_day::<day2::Day2>,
-    run_day::<day3::Day3>,
-    run_day::<day4::Day4>,
-    run_day::<day5::Day5>,
-    run_day::<day6::Day6>,
-    run_day::<UnimplementedDay>,
-    run_day::<UnimplementedDay>,
-    run_day::<day9::Day9>,
-];
+/// Every event has a puzzle for each of days 1 to 25
+pub const DAYS_PER_YEAR: usize = 25;
 
-struct UnimplementedDay;
+/// Solutions for each event, ordered by year
+pub const YEARS: &[(u16, &[Option<Solution>])] = &[(2024, year2024::DAYS)];
 
-impl Day for UnimplementedDay {
-    type Parsed<'a> = ();
+/// Returns the table of solutions for `year`, indexed by `day - 1`.
+///
+/// The table ends at the last implemented day, days after it up to [`DAYS_PER_YEAR`] are
+/// unimplemented.
+#[must_use]
+pub fn days_for_year(year: u16) -> Option<&'static [Option<Solution>]> {
+    YEARS
+        .iter()
+        .find(|&&(y, _)| y == year)
+        .map(|&(_, days)| days)
+}
+
+/// The most recent event with solutions
+#[must_use]
+pub fn latest_year() -> u16 {
+    YEARS.last().expect("at least one year").0
+}
+
+/// Where to read a day's puzzle input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Read `{dir}/{year}/day{day_num}.txt`
+    Dir(PathBuf),
+    /// Read the given file, regardless of the day
+    File(PathBuf),
+    /// Read all of stdin, regardless of the day
+    Stdin,
+}
 
-    fn generator(_input: &str) -> Self::Parsed<'_> {
-        ()
+impl Default for InputSource {
+    fn default() -> Self {
+        Self::Dir(PathBuf::from("input"))
     }
+}
 
-    fn part1(_input: Self::Parsed<'_>) -> impl fmt::Display {
-        "Unimplemented"
+impl InputSource {
+    /// The file the input for the day is read from, `None` for stdin
+    #[must_use]
+    pub fn path(&self, year: u16, day_num: usize) -> Option<PathBuf> {
+        match self {
+            Self::Dir(dir) => Some(dir.join(year.to_string()).join(format!("day{day_num}.txt"))),
+            Self::File(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
     }
 
-    fn part2(_input: Self::Parsed<'_>) -> impl fmt::Display {
-        "Unimplemented"
+    /// The expected answers live next to the input, as `dayN.answers`
+    #[must_use]
+    pub fn answers_path(&self, year: u16, day_num: usize) -> Option<PathBuf> {
+        self.path(year, day_num)
+            .map(|path| path.with_extension("answers"))
+    }
+
+    /// Returns a human-readable description of where the input for the day comes from
+    #[must_use]
+    pub fn describe(&self, year: u16, day_num: usize) -> String {
+        match self.path(year, day_num) {
+            Some(path) => path.display().to_string(),
+            None => "<stdin>".to_owned(),
+        }
+    }
+
+    pub fn read(&self, year: u16, day_num: usize) -> io::Result<String> {
+        match self.path(year, day_num) {
+            Some(path) => fs::read_to_string(path),
+            None => io::read_to_string(io::stdin()),
+        }
     }
 }
 
-#[must_use]
-pub fn fully_run_day(day_num: usize) -> DayResults {
-    let path = format!("input/2024/day{day_num}.txt");
-    let input = std::fs::read_to_string(&path).expect("Failed to read path");
-    let results = DAYS[day_num - 1](&input);
+pub fn read_input(year: u16, day_num: usize, source: &InputSource) -> Result<String, RunnerError> {
+    source
+        .read(year, day_num)
+        .map_err(|source_err| RunnerError::MissingInput {
+            path: source.describe(year, day_num),
+            source: source_err,
+        })
+}
+
+/// Loads the expected answers for a day, `None` if there is no answers file
+pub fn load_answers(
+    year: u16,
+    day_num: usize,
+    source: &InputSource,
+) -> Result<Option<Answers>, RunnerError> {
+    let Some(path) = source.answers_path(year, day_num) else {
+        return Ok(None);
+    };
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(RunnerError::MissingInput {
+                path: path.display().to_string(),
+                source: e,
+            })
+        }
+    };
+    Answers::parse(&contents)
+        .map(Some)
+        .map_err(|message| RunnerError::InvalidAnswers {
+            path: path.display().to_string(),
+            message,
+        })
+}
+
+/// Looks up the solution for a day
+pub fn find_day(year: u16, day_num: usize) -> Result<Solution, RunnerError> {
+    let days = days_for_year(year).ok_or(RunnerError::UnknownYear { year })?;
+    if !(1..=DAYS_PER_YEAR).contains(&day_num) {
+        return Err(RunnerError::UnknownDay { year, day: day_num });
+    }
+    days.get(day_num - 1)
+        .copied()
+        .flatten()
+        .ok_or(RunnerError::UnimplementedDay { year, day: day_num })
+}
+
+/// Solves one part of a day, without printing anything.
+///
+/// Input that breaks the assumptions `part` makes is rejected with every violation, see
+/// [`Day::validate`], rather than given to a solution that could panic, overflow or answer wrongly.
+/// Assumptions only the other part makes aren't checked.
+pub fn solve(year: u16, day_num: usize, part: Part, input: &str) -> Result<Answer, RunnerError> {
+    let solution = find_day(year, day_num)?;
+    let violations = (solution.validate)(input, Some(part));
+    if !violations.is_empty() {
+        return Err(RunnerError::ViolatedAssumptions {
+            year,
+            day: day_num,
+            violations,
+        });
+    }
+    let solve_part = match part {
+        Part::One => solution.part1,
+        Part::Two => solution.part2,
+    };
+    solve_part(input).map_err(|error| RunnerError::InvalidInput {
+        year,
+        day: day_num,
+        error,
+    })
+}
+
+/// Every implemented day as `(year, day)`, in order
+pub fn list_days() -> impl Iterator<Item = (u16, usize)> {
+    YEARS.iter().flat_map(|&(year, days)| {
+        (1..)
+            .zip(days)
+            .filter(|(_, solution)| solution.is_some())
+            .map(move |(day, _)| (year, day))
+    })
+}
+
+/// Runs a solution, turning malformed input into a [`RunnerError::InvalidInput`], a panic into a
+/// [`RunnerError::SolverFailure`], and a run over `options.timeout` into a
+/// [`RunnerError::Timeout`]
+pub fn try_run(
+    year: u16,
+    day_num: usize,
+    run: DayFn,
+    input: &str,
+    options: &RunOptions,
+) -> Result<DayResults, RunnerError> {
+    match options.timeout {
+        Some(timeout) => watchdog::run(year, day_num, run, input, options, timeout),
+        None => catch_panic(year, day_num, run, input, options),
+    }
+}
+
+fn catch_panic(
+    year: u16,
+    day_num: usize,
+    run: DayFn,
+    input: &str,
+    options: &RunOptions,
+) -> Result<DayResults, RunnerError> {
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| run(input, options))).map_err(
+        |payload| {
+            let message = if let Some(s) = payload.downcast_ref::<&str>() {
+                (*s).to_owned()
+            } else if let Some(s) = payload.downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "panicked".to_owned()
+            };
+            RunnerError::SolverFailure {
+                year,
+                day: day_num,
+                message,
+            }
+        },
+    )?;
+    result.map_err(|error| RunnerError::InvalidInput {
+        year,
+        day: day_num,
+        error,
+    })
+}
+
+/// Runs the day with input from `source`, printing the results.
+pub fn fully_run_day(
+    year: u16,
+    day_num: usize,
+    source: &InputSource,
+    options: &RunOptions,
+) -> Result<DayResults, RunnerError> {
+    let solution = find_day(year, day_num)?;
+    let input = read_input(year, day_num, source)?;
+    let results = try_run(year, day_num, solution.run, &input, options)?;
     println!("Day {day_num}: {results}");
-    results
+    Ok(results)
 }
 
-fn part_1_impl<'a, D: Day + 'a>(input: &'a str) -> impl fmt::Display + 'a {
-    let parsed = D::generator(input);
-    D::part1(parsed)
+fn part_1_impl<D: Day>(input: &str) -> Answer {
+    let parsed = D::generator(input).unwrap_or_else(|e| panic!("invalid input: {e}"));
+    D::part1(parsed).into()
 }
 
-fn part_2_impl<'a, D: Day + 'a>(input: &'a str) -> impl fmt::Display + 'a {
-    let parsed = D::generator(input);
-    D::part2(parsed)
+fn part_2_impl<D: Day>(input: &str) -> Answer {
+    let parsed = D::generator(input).unwrap_or_else(|e| panic!("invalid input: {e}"));
+    D::part2(parsed).into()
 }
 
 macro_rules! codspeed_def {
     ($day_ty:ty) => {
         #[must_use]
-        pub fn part1(input: &str) -> impl ::std::fmt::Display + '_ {
+        pub fn part1(input: &str) -> impl ::std::fmt::Display {
             $crate::part_1_impl::<$day_ty>(input)
         }
 
         #[must_use]
-        pub fn part2(input: &str) -> impl ::std::fmt::Display + '_ {
+        pub fn part2(input: &str) -> impl ::std::fmt::Display {
             $crate::part_2_impl::<$day_ty>(input)
         }
     };
 }
 
 use codspeed_def;
+
+/// Declares the solutions for a year as `N => dayN::DayN` entries, in any order.
+///
+/// This declares each `dayN` module and builds the `DAYS` table, indexed by `day - 1` with `None`
+/// for the gaps, and a `modules` module re-exporting every `dayN` module. Registering a day twice,
+/// or under a module not named after its day, fails to compile.
+macro_rules! register_days {
+    ($($day_num:literal => $module:ident :: $day_ty:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every `dayN` module, for glob re-exporting
+        pub mod modules {
+            $(pub use super::$module;)*
+        }
+
+        /// Indexed by `day - 1`, `None` for days without a solution
+        pub const DAYS: &[Option<$crate::Solution>] = &{
+            const LEN: usize = $crate::max_day(&[$($day_num),*]);
+            let mut days: [Option<$crate::Solution>; LEN] = [None; LEN];
+            $(
+                assert!(
+                    $crate::is_day_module(stringify!($module), $day_num),
+                    concat!("day ", $day_num, " must be in module day", $day_num),
+                );
+                days[$day_num - 1] = Some($crate::Solution::new::<$module::$day_ty>());
+            )*
+            days
+        };
+    };
+}
+
+use register_days;
+
+const fn max_day(days: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < days.len() {
+        assert!(days[i] > 0, "days start at 1");
+        assert!(days[i] <= DAYS_PER_YEAR, "there are only 25 days");
+        if days[i] > max {
+            max = days[i];
+        }
+        i += 1;
+    }
+    max
+}
+
+/// Whether `module` is `day{day_num}`
+const fn is_day_module(module: &str, day_num: usize) -> bool {
+    let module = module.as_bytes();
+    if module.len() <= 3 || module[0] != b'd' || module[1] != b'a' || module[2] != b'y' {
+        return false;
+    }
+    let mut parsed = 0;
+    let mut i = 3;
+    while i < module.len() {
+        if !module[i].is_ascii_digit() {
+            return false;
+        }
+        parsed = parsed * 10 + (module[i] - b'0') as usize;
+        i += 1;
+    }
+    parsed == day_num
+}
+
+/// Checks `D` against a straightforward `reference` solution on `cases` random inputs.
+///
+/// `reference` returns the answers to both parts. Each input comes from [`Day::generate_input`]
+/// with its own seed and a random scale up to `max_scale`, which are included in any failure so
+/// the input can be reproduced.
+#[cfg(test)]
+fn check_against_reference<D: Day, R1: Into<Answer>, R2: Into<Answer>>(
+    cases: u64,
+    max_scale: f64,
+    reference: impl Fn(&str) -> (R1, R2),
+) {
+    for seed in 0..cases {
+        let mut rng = Rng::new(seed);
+        let scale = max_scale * rng.range(1..=100) as f64 / 100.0;
+        let input = D::generate_input(&mut rng, scale).expect("the day has an input generator");
+        let (part1, part2) = reference(&input);
+        let (part1, part2) = (part1.into(), part2.into());
+        let parsed = D::generator(&input)
+            .unwrap_or_else(|e| panic!("seed {seed} generated invalid input: {e}\n{input}"));
+        let context = format!("seed {seed}, scale {scale}, input:\n{input}");
+        assert_eq!(D::part1(parsed.clone()).into(), part1, "part 1, {context}");
+        assert_eq!(D::part2(parsed.clone()).into(), part2, "part 2, {context}");
+        let (solved1, solved2) = D::solve(parsed);
+        assert_eq!(
+            (solved1.into(), solved2.into()),
+            (part1, part2),
+            "solve, {context}"
+        );
+    }
+}
+
+#[test]
+fn generated_inputs() {
+    for &(year, days) in YEARS {
+        for (solution, day) in days.iter().zip(1..) {
+            let Some(solution) = solution else { continue };
+            for (seed, scale) in [(0, 1.0), (1, 2.0)] {
+                let Some(input) = (solution.generate_input)(&mut Rng::new(seed), scale) else {
+                    continue;
+                };
+                let name = format!("{year} day {day} with seed {seed} and scale {scale}");
+                let violations = (solution.validate)(&input, None);
+                assert!(violations.is_empty(), "{name}: {}", violations[0]);
+                let results = try_run(year, day, solution.run, &input, &RunOptions::default())
+                    .unwrap_or_else(|e| panic!("{name}: {e}"));
+                assert!(results.part1.is_some_and(|answer| answer.is_implemented()));
+            }
+        }
+    }
+}
+
+/// A cheap in-tree version of the fuzz targets, parts must not panic on input that validates
+#[test]
+fn mutated_examples() {
+    const BYTES: &[u8] = b"0123456789 \n,|#.^XMASmul(do)n't";
+    let mut rng = Rng::new(0);
+    for &(_, days) in YEARS {
+        for solution in days.iter().flatten() {
+            for example in solution.examples {
+                for _ in 0..500 {
+                    let mut input = example.input.as_bytes().to_vec();
+                    for _ in 0..rng.range(1..=4) {
+                        let i = rng.range(0..=input.len() as u64) as usize;
+                        let b = BYTES[rng.range(0..=BYTES.len() as u64 - 1) as usize];
+                        match rng.range(0..=2) {
+                            0 if i < input.len() => input[i] = b,
+                            1 if i < input.len() => _ = input.remove(i),
+                            _ => input.insert(i, b),
+                        }
+                    }
+                    let input = String::from_utf8(input).expect("only ASCII is inserted");
+                    if (solution.validate)(&input, Some(Part::One)).is_empty() {
+                        _ = (solution.part1)(&input);
+                    }
+                    if (solution.validate)(&input, Some(Part::Two)).is_empty() {
+                        _ = (solution.part2)(&input);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn day_registration() {
+    assert!(is_day_module("day9", 9));
+    assert!(is_day_module("day25", 25));
+    assert!(!is_day_module("day9", 7));
+    assert!(!is_day_module("day", 0));
+    assert!(!is_day_module("dayx", 0));
+    assert_eq!(max_day(&[1, 9, 6]), 9);
+    assert_eq!(year2024::DAYS.iter().filter(|day| day.is_none()).count(), 2);
+    assert!(matches!(
+        find_day(2024, 12),
+        Err(RunnerError::UnimplementedDay { day: 12, .. })
+    ));
+    assert!(matches!(
+        find_day(2024, 26),
+        Err(RunnerError::UnknownDay { day: 26, .. })
+    ));
+}
+
+#[test]
+fn examples() {
+    for &(year, days) in YEARS {
+        for (solution, day) in days.iter().zip(1..) {
+            let Some(solution) = solution else { continue };
+            for (i, example) in solution.examples.iter().enumerate() {
+                let name = format!("{year} day {day} example {}", i + 1);
+                let results = try_run(
+                    year,
+                    day,
+                    solution.run,
+                    example.input,
+                    &example.run_options(),
+                )
+                .unwrap_or_else(|e| panic!("{name}: {e}"));
+                let answers = example.answers();
+                for (part, run_answer, single) in [
+                    (Part::One, &results.part1, solution.part1),
+                    (Part::Two, &results.part2, solution.part2),
+                ] {
+                    let Some(expected) = example.answer(part) else {
+                        continue;
+                    };
+                    let single = single(example.input).unwrap_or_else(|e| panic!("{name}: {e}"));
+                    for actual in [run_answer.as_ref().unwrap(), &single] {
+                        assert_eq!(
+                            answers.check(part, actual),
+                            answers::Verdict::Pass,
+                            "{name} part {part}: expected {expected}, got {actual}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn library_api() {
+    let days: Vec<_> = list_days().collect();
+    assert!(days.contains(&(2024, 1)) && days.contains(&(2024, 9)));
+    assert!(!days.contains(&(2024, 7)));
+
+    let example = year2024::day1::Day1::EXAMPLES[0];
+    let answer = solve(2024, 1, Part::Two, example.input).unwrap();
+    assert_eq!(answer.to_string(), "31");
+    assert!(matches!(
+        solve(2024, 7, Part::One, ""),
+        Err(RunnerError::UnimplementedDay { year: 2024, day: 7 })
+    ));
+    assert!(matches!(
+        solve(2024, 1, Part::One, "3   x\n"),
+        Err(RunnerError::InvalidInput { day: 1, .. })
+    ));
+    assert!(matches!(
+        solve(2024, 6, Part::One, ".#.\n#^#\n.#.\n"),
+        Err(RunnerError::ViolatedAssumptions { day: 6, .. })
+    ));
+    // Only part 2 needs the rules to order every pair of pages
+    let unordered = "47|53\n\n47,61,53\n";
+    assert_eq!(
+        solve(2024, 5, Part::One, unordered).unwrap().to_string(),
+        "61"
+    );
+    assert!(matches!(
+        solve(2024, 5, Part::Two, unordered),
+        Err(RunnerError::ViolatedAssumptions { day: 5, .. })
+    ));
+}
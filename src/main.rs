@@ -1,20 +1,596 @@
-use aoc_2024::{fully_run_day, DAYS};
-fn main() {
-    let mut total_time = std::time::Duration::ZERO;
-    if let Some(day) = std::env::args()
-        .nth(1)
-        .and_then(|s| s.parse::<usize>().ok())
-    {
-        let results = fully_run_day(day);
-        println!();
-        total_time += results.timing.gen + results.timing.part1 + results.timing.part2;
+use aoc_2024::allocs;
+use aoc_2024::answers::{Answers, Expected, Verdict};
+use aoc_2024::baseline::{self, Baseline, Environment};
+use aoc_2024::{
+    days_for_year, find_day, latest_year, load_answers, read_input, try_run, DayResults, Part, Rng,
+    RunnerError, DAYS_PER_YEAR, YEARS,
+};
+use cli::{Args, Command, Format};
+use export::{Record, ReportRow};
+use std::fs;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+mod cli;
+mod export;
+mod isolate;
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOCATOR: aoc_2024::allocs::CountingAllocator = aoc_2024::allocs::CountingAllocator;
+
+fn main() -> ExitCode {
+    if isolate::is_child() {
+        return isolate::child_main();
+    }
+
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.allocs && !allocs::is_installed() {
+        eprintln!("error: counting allocations requires building with `--features count-allocs`");
+        return ExitCode::from(2);
+    }
+
+    match args.command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Command::List => {
+            let mut status = Status::default();
+            status.record(list(args.year));
+            return status.exit_code();
+        }
+        _ => {}
+    }
+
+    let year = args.year.unwrap_or_else(latest_year);
+    let Some(solutions) = days_for_year(year) else {
+        return Status::default().fail(RunnerError::UnknownYear { year });
+    };
+    let days = match args.days.clone() {
+        Some(days) => days,
+        // Up to the last implemented day, so only gaps before it are reported
+        None => (1..=solutions.len()).collect(),
+    };
+
+    let mut status = Status {
+        // Gaps are only an error if the day was explicitly asked for
+        skip_unimplemented: args.days.is_none(),
+        // Don't interleave anything with machine readable output
+        quiet: args.format != Format::Text || args.command == Command::Report,
+        ..Status::default()
+    };
+    match args.command {
+        Command::Run | Command::Validate => run(&args, year, &days, &mut status),
+        Command::Bench => bench(&args, year, &days, &mut status),
+        Command::Check => check(&args, year, &days, &mut status),
+        Command::Report => report(&args, year, &days, &mut status),
+        Command::Watch => watch(&args, year, &days, &mut status),
+        Command::GenInput => gen_input(&args, year, days[0], &mut status),
+        Command::Help | Command::List => unreachable!(),
+    }
+    status.exit_code()
+}
+
+/// Tracks the first error of a run, so later days still run
+#[derive(Default)]
+struct Status {
+    skip_unimplemented: bool,
+    quiet: bool,
+    exit_code: Option<u8>,
+}
+
+impl Status {
+    fn record<T>(&mut self, result: Result<T, RunnerError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(RunnerError::UnimplementedDay { day, .. }) if self.skip_unimplemented => {
+                if !self.quiet {
+                    println!("Day {day}: Unimplemented");
+                }
+                None
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                self.exit_code.get_or_insert(e.exit_code());
+                None
+            }
+        }
+    }
+
+    fn fail(mut self, e: RunnerError) -> ExitCode {
+        self.record::<()>(Err(e));
+        self.exit_code()
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::from(self.exit_code.unwrap_or(0))
+    }
+}
+
+fn list(year: Option<u16>) -> Result<(), RunnerError> {
+    if let Some(year) = year.filter(|&year| days_for_year(year).is_none()) {
+        return Err(RunnerError::UnknownYear { year });
+    }
+    for &(y, days) in YEARS {
+        if year.is_some_and(|year| year != y) {
+            continue;
+        }
+        println!("{y}:");
+        for day in 1..=DAYS_PER_YEAR {
+            let status = if days.get(day - 1).is_some_and(Option::is_some) {
+                "implemented"
+            } else {
+                "unimplemented"
+            };
+            println!("  Day {day}: {status}");
+        }
+    }
+    Ok(())
+}
+
+/// Runs a day without printing anything
+fn solve_day(args: &Args, year: u16, day: usize) -> Result<DayResults, RunnerError> {
+    let solution = find_day(year, day)?;
+    let input = read_input(year, day, &args.input)?;
+    if args.command == Command::Validate {
+        let violations = (solution.validate)(&input, args.options.part);
+        if !violations.is_empty() {
+            return Err(RunnerError::ViolatedAssumptions {
+                year,
+                day,
+                violations,
+            });
+        }
+    }
+    if args.isolate {
+        isolate::solve_day(year, day, &input, &args.options)
     } else {
-        for i in 0..DAYS.len() {
-            let results = fully_run_day(i + 1);
-            println!();
-            total_time += results.timing.gen + results.timing.part1 + results.timing.part2;
+        try_run(year, day, solution.run, &input, &args.options)
+    }
+}
+
+/// Runs every day, calling `f` with the results of each successful day in order.
+///
+/// With more than one job, days run concurrently and `f` is only called once all are done.
+fn for_each_day(
+    args: &Args,
+    year: u16,
+    days: &[usize],
+    status: &mut Status,
+    mut f: impl FnMut(&mut Status, usize, DayResults),
+) {
+    let mut handle = |status: &mut Status, day, result| {
+        let Some(results) = status.record(result) else {
+            return;
+        };
+        if args.strict_allocs {
+            check_allocs(year, day, &results, status);
+        }
+        f(status, day, results);
+    };
+
+    if args.jobs <= 1 {
+        for &day in days {
+            handle(status, day, solve_day(args, year, day));
         }
+        return;
     }
 
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<_> = thread::scope(|s| {
+        let workers: Vec<_> = (0..args.jobs.min(days.len()))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    while let Some(&day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        done.push((day, solve_day(args, year, day)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("panics are caught by try_run"))
+            .collect()
+    });
+    results.sort_by_key(|&(day, _)| day);
+    for (day, result) in results {
+        handle(status, day, result);
+    }
+}
+
+/// Records an error for each allocation-free part that allocated
+fn check_allocs(year: u16, day: usize, results: &DayResults, status: &mut Status) {
+    for &part in results.allocation_free {
+        let (answer, allocs) = match part {
+            Part::One => (&results.part1, &results.allocs.part1),
+            Part::Two => (&results.part2, &results.allocs.part2),
+        };
+        if answer.is_some() && allocs.allocations != 0 {
+            status.record::<()>(Err(RunnerError::UnexpectedAllocation {
+                year,
+                day,
+                part,
+                allocations: allocs.allocations,
+            }));
+        }
+    }
+}
+
+fn print_allocs(results: &DayResults) {
+    let phases = [
+        ("gen", &results.allocs.gen, true),
+        ("part 1", &results.allocs.part1, results.part1.is_some()),
+        ("part 2", &results.allocs.part2, results.part2.is_some()),
+    ];
+    let phases: Vec<_> = phases
+        .into_iter()
+        .filter(|&(_, _, ran)| ran)
+        .map(|(name, allocs, _)| {
+            format!(
+                "{name} {} ({} B, peak {} B)",
+                allocs.allocations, allocs.bytes, allocs.peak_bytes
+            )
+        })
+        .collect();
+    println!("Allocations: {}", phases.join(", "));
+}
+
+fn print_peak_rss() {
+    if let Some(rss) = allocs::peak_rss() {
+        println!("Peak RSS: {:.1} MiB", rss as f64 / (1024.0 * 1024.0));
+    }
+}
+
+/// Runs every day, collecting the results
+fn solve_days(args: &Args, year: u16, days: &[usize], status: &mut Status) -> Vec<Record> {
+    let mut records = Vec::new();
+    for_each_day(args, year, days, status, |_, day, results| {
+        records.push(Record { year, day, results });
+    });
+    records
+}
+
+/// Prints the results in a machine readable format
+fn export(args: &Args, mode: &str, records: &[Record]) {
+    let env = Environment::detect();
+    match args.format {
+        Format::Json => print!("{}", export::json(mode, &env, records)),
+        Format::Csv => print!("{}", baseline::csv(&env, export::rows(mode, records))),
+        Format::Text => unreachable!(),
+    }
+}
+
+fn run(args: &Args, year: u16, days: &[usize], status: &mut Status) {
+    if args.format != Format::Text {
+        let records = solve_days(args, year, days, status);
+        return export(args, "run", &records);
+    }
+    let mut total_time = None;
+    for_each_day(args, year, days, status, |status, day, results| {
+        println!("Day {day}: {results}");
+        if args.allocs {
+            print_allocs(&results);
+        }
+        *total_time.get_or_insert(Duration::ZERO) += results.timing.total();
+        if let Some(Some(answers)) = status.record(load_answers(year, day, &args.input)) {
+            verify(year, day, &results, &answers, status);
+        }
+        println!();
+    });
+
+    // Nothing to total if every day failed
+    let Some(total_time) = total_time else {
+        return;
+    };
     println!("Total time: {total_time:?}");
+    if args.allocs {
+        print_peak_rss();
+    }
+}
+
+fn bench(args: &Args, year: u16, days: &[usize], status: &mut Status) {
+    let Some(baseline) = status.record(
+        args.baseline
+            .as_deref()
+            .map(|path| Baseline::load(path, "bench"))
+            .transpose(),
+    ) else {
+        return;
+    };
+
+    let records = solve_days(args, year, days, status);
+    if args.format != Format::Text {
+        export(args, "bench", &records);
+    } else if !records.is_empty() {
+        for record in &records {
+            print_bench(args, record);
+        }
+        let total_time: Duration = records.iter().map(|r| r.results.timing.total()).sum();
+        println!("Total median time: {total_time:?}");
+        if args.allocs {
+            print_peak_rss();
+        }
+    }
+
+    let rows: Vec<_> = export::rows("bench", &records).collect();
+    let errors = baseline::check(
+        year,
+        &rows,
+        baseline.as_ref(),
+        &args.budgets,
+        args.threshold,
+    );
+    for e in errors {
+        status.record::<()>(Err(e));
+    }
+
+    if let Some(path) = &args.save_baseline {
+        let csv = baseline::csv(&Environment::detect(), rows);
+        if let Err(e) = fs::write(path, csv) {
+            status.record::<()>(Err(RunnerError::BaselineFile {
+                path: path.clone(),
+                message: format!("failed to write: {e}"),
+            }));
+        }
+    }
+}
+
+fn print_bench(args: &Args, record: &Record) {
+    let results = &record.results;
+    println!(
+        "Day {} ({} samples, {} warmup runs):",
+        record.day, args.options.samples, args.options.warmup
+    );
+    println!(
+        "  {:<8} {:>12} {:>12} {:>12} {:>12}",
+        "", "min", "median", "mean", "stddev"
+    );
+    let stats = results.stats();
+    let phases = [
+        ("Gen", &stats.gen, Some(String::new())),
+        (
+            "Part 1",
+            &stats.part1,
+            results.part1.as_ref().map(ToString::to_string),
+        ),
+        (
+            "Part 2",
+            &stats.part2,
+            results.part2.as_ref().map(ToString::to_string),
+        ),
+    ];
+    for (name, stats, answer) in phases {
+        let Some(answer) = answer else {
+            continue;
+        };
+        let line = format!(
+            "  {:<8} {:>12?} {:>12?} {:>12?} {:>12?}  {answer}",
+            name, stats.min, stats.median, stats.mean, stats.stddev
+        );
+        println!("{}", line.trim_end());
+    }
+    if args.allocs {
+        print!("  ");
+        print_allocs(results);
+    }
+    println!();
+}
+
+fn check(args: &Args, year: u16, days: &[usize], status: &mut Status) {
+    if args.examples {
+        check_examples(args, year, days, status);
+        return;
+    }
+    for_each_day(args, year, days, status, |status, day, results| {
+        if args.record {
+            record(args, year, day, &results, status);
+            return;
+        }
+        if let Some(answers) = status.record(load_answers(year, day, &args.input)) {
+            verify(year, day, &results, &answers.unwrap_or_default(), status);
+        }
+    });
+}
+
+/// Runs the selected days on their puzzle examples, comparing the answers from the puzzle text
+fn check_examples(args: &Args, year: u16, days: &[usize], status: &mut Status) {
+    for &day in days {
+        let Some(solution) = status.record(find_day(year, day)) else {
+            continue;
+        };
+        if solution.examples.is_empty() {
+            println!("Day {day}: no examples");
+        }
+        for (i, example) in solution.examples.iter().enumerate() {
+            let mut options = example.run_options();
+            if let Some(part) = args.options.part {
+                if example.answer(part).is_none() {
+                    continue;
+                }
+                options.part = Some(part);
+            }
+            options.timeout = args.options.timeout;
+            let result = if args.isolate {
+                isolate::solve_day(year, day, example.input, &options)
+            } else {
+                try_run(year, day, solution.run, example.input, &options)
+            };
+            if let Some(results) = status.record(result) {
+                let name = format!("Day {day} example {}", i + 1);
+                verify_named(&name, year, day, &results, &example.answers(), status);
+            }
+        }
+    }
+}
+
+fn report(args: &Args, year: u16, days: &[usize], status: &mut Status) {
+    let records = solve_days(args, year, days, status);
+    let mut rows = Vec::with_capacity(days.len());
+    for &day in days {
+        let Some(record) = records.iter().find(|record| record.day == day) else {
+            rows.push(match find_day(year, day) {
+                Err(RunnerError::UnimplementedDay { .. }) => ReportRow::Unimplemented { day },
+                _ => ReportRow::Failed { day },
+            });
+            continue;
+        };
+        let answers = status
+            .record(load_answers(year, day, &args.input))
+            .flatten()
+            .unwrap_or_default();
+        let results = &record.results;
+        let verdicts: Vec<_> = [(Part::One, &results.part1), (Part::Two, &results.part2)]
+            .into_iter()
+            .filter_map(|(part, actual)| Some(answers.check(part, actual.as_ref()?)))
+            .collect();
+        if verdicts
+            .iter()
+            .any(|verdict| matches!(verdict, Verdict::Fail { .. }))
+        {
+            status.record::<()>(Err(RunnerError::WrongAnswer { year, day }));
+        }
+        rows.push(ReportRow::Solved { record, verdicts });
+    }
+    print!("{}", export::markdown(&rows));
+}
+
+/// Prints a random input for the day
+fn gen_input(args: &Args, year: u16, day: usize, status: &mut Status) {
+    let Some(solution) = status.record(find_day(year, day)) else {
+        return;
+    };
+    match (solution.generate_input)(&mut Rng::new(args.seed), args.scale) {
+        Some(input) => print!("{input}"),
+        None => {
+            status.record::<()>(Err(RunnerError::NoInputGenerator { year, day }));
+        }
+    }
+}
+
+/// Polls the input files of the selected days forever, re-running a day when its input changes
+fn watch(args: &Args, year: u16, days: &[usize], status: &mut Status) {
+    let days: Vec<usize> = days
+        .iter()
+        .copied()
+        .filter(|&day| status.record(find_day(year, day)).is_some())
+        .collect();
+    if days.is_empty() {
+        return;
+    }
+
+    let mut last_modified: Vec<Option<SystemTime>> = vec![None; days.len()];
+    let mut first = true;
+    loop {
+        let mut changed = Vec::new();
+        for (&day, last_modified) in days.iter().zip(&mut last_modified) {
+            let path = args
+                .input
+                .path(year, day)
+                .expect("stdin is rejected by the cli");
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if modified == *last_modified && !first {
+                continue;
+            }
+            *last_modified = modified;
+            if modified.is_none() {
+                println!("Day {day}: waiting for {}", path.display());
+                continue;
+            }
+            changed.push(day);
+        }
+        // The same path as `run`, so --isolate, --jobs and the alloc flags apply
+        for_each_day(args, year, &changed, status, |status, day, results| {
+            println!("Day {day}: {results}");
+            if args.allocs {
+                print_allocs(&results);
+            }
+            if let Some(Some(answers)) = status.record(load_answers(year, day, &args.input)) {
+                verify(year, day, &results, &answers, status);
+            }
+            println!();
+        });
+        first = false;
+        thread::sleep(args.interval);
+    }
+}
+
+/// Prints PASS/FAIL/UNKNOWN for each part that was run, recording any mismatch
+fn verify(year: u16, day: usize, results: &DayResults, answers: &Answers, status: &mut Status) {
+    verify_named(&format!("Day {day}"), year, day, results, answers, status);
+}
+
+/// Like [`verify`], labelling each line with `name`
+fn verify_named(
+    name: &str,
+    year: u16,
+    day: usize,
+    results: &DayResults,
+    answers: &Answers,
+    status: &mut Status,
+) {
+    let mut all_passed = true;
+    for (part, actual) in [(Part::One, &results.part1), (Part::Two, &results.part2)] {
+        let Some(actual) = actual else {
+            continue;
+        };
+        match answers.check(part, actual) {
+            Verdict::Pass => println!("{name} part {part}: PASS ({actual})"),
+            Verdict::Unknown => println!("{name} part {part}: UNKNOWN ({actual})"),
+            Verdict::Unimplemented => println!("{name} part {part}: UNIMPLEMENTED"),
+            Verdict::Fail { expected } => {
+                all_passed = false;
+                println!("{name} part {part}: FAIL");
+                println!("  expected: {expected}");
+                println!("  actual:   {actual}");
+            }
+        }
+    }
+    if !all_passed {
+        status.record::<()>(Err(RunnerError::WrongAnswer { year, day }));
+    }
+}
+
+/// Saves the answers from `results` as the expected answers for the day
+fn record(args: &Args, year: u16, day: usize, results: &DayResults, status: &mut Status) {
+    let path = args
+        .input
+        .answers_path(year, day)
+        .expect("stdin is rejected by the cli");
+    let Some(mut answers) = status.record(load_answers(year, day, &args.input)) else {
+        return;
+    };
+    let answers = answers.get_or_insert_default();
+    for (slot, actual) in [
+        (&mut answers.part1, &results.part1),
+        (&mut answers.part2, &results.part2),
+    ] {
+        if let Some(actual) = actual.as_ref().filter(|actual| actual.is_implemented()) {
+            let actual = actual.to_string();
+            *slot = Some(if args.hash {
+                Expected::hashed(&actual)
+            } else {
+                Expected::Plain(actual)
+            });
+        }
+    }
+    let written =
+        fs::write(&path, answers.to_string()).map_err(|source| RunnerError::AnswersNotSaved {
+            path: path.display().to_string(),
+            source,
+        });
+    if status.record(written).is_some() {
+        println!("Day {day}: recorded answers to {}", path.display());
+    }
 }
@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// Malformed puzzle input, pointing at the first offending byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset into the input, may be the length of the input for an early end
+    pub offset: usize,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in characters
+    pub column: usize,
+    pub message: String,
+    /// The text of the offending line, without the newline
+    pub line_text: String,
+}
+
+impl ParseError {
+    #[must_use]
+    pub fn new(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        // Offsets from byte-wise parsers may land inside a multi-byte character
+        let char_start = (0..=offset)
+            .rev()
+            .find(|&i| input.is_char_boundary(i))
+            .unwrap_or(0);
+        let line_start = input[..char_start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[line_start..]
+            .find('\n')
+            .map_or(input.len(), |i| line_start + i);
+        Self {
+            offset,
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..char_start].chars().count() + 1,
+            message: message.into(),
+            line_text: input[line_start..line_end].to_owned(),
+        }
+    }
+
+    /// An error pointing at the start of `part`, which must be a slice of `input`
+    #[must_use]
+    pub fn at(input: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .expect("`part` must be a slice of `input`");
+        Self::new(input, offset, message)
+    }
+
+    /// The offending line with a caret under the offending column
+    #[must_use]
+    pub fn snippet(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "{gutter} |\n{} | {}\n{gutter} | {}^",
+            self.line,
+            self.line_text,
+            " ".repeat(self.column - 1)
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}\n{}",
+            self.line,
+            self.column,
+            self.message,
+            self.snippet()
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The bytes of line-based `input`, treating a missing final newline as if it was there, so a
+/// parser can finish every line on `\n`. Empty input stays empty.
+pub(crate) fn with_final_newline(input: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    let missing_newline = input.last().is_some_and(|&b| b != b'\n');
+    input
+        .iter()
+        .copied()
+        .chain(missing_newline.then_some(b'\n'))
+}
+
+#[test]
+fn position() {
+    let input = "3   4\n4 x 3\n";
+    let err = ParseError::new(input, 8, "expected a digit");
+    assert_eq!((err.line, err.column), (2, 3));
+    assert_eq!(err.line_text, "4 x 3");
+    assert_eq!(err.snippet(), "  |\n2 | 4 x 3\n  |   ^");
+
+    let end = ParseError::new(input, input.len(), "unexpected end of input");
+    assert_eq!((end.line, end.column, end.line_text.as_str()), (3, 1, ""));
+}
+
+#[test]
+fn final_newline() {
+    let bytes = |input: &str| with_final_newline(input.as_bytes()).collect::<Vec<_>>();
+    assert_eq!(bytes("1\n2"), b"1\n2\n");
+    assert_eq!(bytes("1\n2\n"), b"1\n2\n");
+    assert_eq!(bytes(""), b"");
+}
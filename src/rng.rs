@@ -0,0 +1,56 @@
+//! A small seeded random number generator, so random inputs can be reproduced from their seed
+
+use std::ops::RangeInclusive;
+
+/// SplitMix64, plenty for generating test inputs but not for anything needing real randomness
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, very slightly biased towards the low end for huge ranges
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range");
+        match (end - start).checked_add(1) {
+            Some(len) => start + self.next_u64() % len,
+            None => self.next_u64(),
+        }
+    }
+
+    /// True `numerator` times out of `denominator`
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(1..=denominator) <= numerator
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0..=i as u64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[test]
+fn reproducible() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    for _ in 0..100 {
+        let n = a.range(3..=7);
+        assert_eq!(n, b.range(3..=7));
+        assert!((3..=7).contains(&n));
+    }
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+}
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+/// Summary of repeated timings of a single phase
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    /// Sample standard deviation, zero with fewer than two samples
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// All fields are zero if there are no samples
+    #[must_use]
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let n = sorted.len();
+        let median = if n % 2 == 0 {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean_nanos = sorted.iter().map(Duration::as_nanos).sum::<u128>() as f64 / n as f64;
+        let stddev_nanos = if n < 2 {
+            0.0
+        } else {
+            let sum_sq: f64 = sorted
+                .iter()
+                .map(|d| (d.as_nanos() as f64 - mean_nanos).powi(2))
+                .sum();
+            (sum_sq / (n - 1) as f64).sqrt()
+        };
+
+        Self {
+            samples: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean_nanos.round() as u64),
+            stddev: Duration::from_nanos(stddev_nanos.round() as u64),
+        }
+    }
+}
+
+impl Stats {
+    /// Welch's t statistic for the mean of `self` being larger than the mean of `baseline`.
+    ///
+    /// `None` if either has fewer than two samples.
+    #[must_use]
+    pub fn welch_t(&self, baseline: &Self) -> Option<f64> {
+        if self.samples < 2 || baseline.samples < 2 {
+            return None;
+        }
+        let variance_of_mean = |stats: &Self| {
+            let stddev = stats.stddev.as_nanos() as f64;
+            stddev * stddev / stats.samples as f64
+        };
+        let diff = self.mean.as_nanos() as f64 - baseline.mean.as_nanos() as f64;
+        let std_err = (variance_of_mean(self) + variance_of_mean(baseline)).sqrt();
+        Some(if std_err == 0.0 {
+            // Both perfectly consistent: any difference is significant
+            diff.signum() * f64::INFINITY
+        } else {
+            diff / std_err
+        })
+    }
+}
+
+#[test]
+fn simple_stats() {
+    let samples = [5, 1, 3, 2, 4].map(Duration::from_nanos);
+    let stats = Stats::from_samples(&samples);
+    assert_eq!(stats.samples, 5);
+    assert_eq!(stats.min, Duration::from_nanos(1));
+    assert_eq!(stats.median, Duration::from_nanos(3));
+    assert_eq!(stats.mean, Duration::from_nanos(3));
+    // sqrt(10 / 4)
+    assert_eq!(stats.stddev, Duration::from_nanos(2));
+
+    let even = Stats::from_samples(&[10, 20, 30, 40].map(Duration::from_nanos));
+    assert_eq!(even.median, Duration::from_nanos(25));
+
+    assert_eq!(Stats::from_samples(&[]), Stats::default());
+}
+
+#[test]
+fn welch() {
+    let stats = |mean, stddev| Stats {
+        samples: 100,
+        mean: Duration::from_nanos(mean),
+        stddev: Duration::from_nanos(stddev),
+        ..Stats::default()
+    };
+    let t = stats(110, 10).welch_t(&stats(100, 10)).unwrap();
+    assert!((t - 7.07).abs() < 0.01, "{t}");
+    assert!(stats(100, 10).welch_t(&stats(110, 10)).unwrap() < 0.0);
+    assert_eq!(stats(101, 0).welch_t(&stats(100, 0)), Some(f64::INFINITY));
+    assert_eq!(Stats::default().welch_t(&stats(100, 10)), None);
+}
@@ -0,0 +1,137 @@
+//! Per-part timeouts.
+//!
+//! A hung solution can't be stopped from the outside, so with a timeout the day runs on its own
+//! thread while the caller watches which phases are running and for how long. On timeout the
+//! thread is abandoned, and keeps running in the background until the process exits.
+
+use crate::{DayFn, DayResults, Part, RunOptions, RunnerError};
+use std::cell::RefCell;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The phases a day is currently running, with `None` for the generator
+#[derive(Debug, Default)]
+pub(crate) struct Progress {
+    running: Mutex<Vec<(Option<Part>, Instant)>>,
+}
+
+impl Progress {
+    fn start(&self, phase: Option<Part>) {
+        let mut running = self.running.lock().unwrap();
+        running.retain(|&(p, _)| p != phase);
+        running.push((phase, Instant::now()));
+    }
+
+    fn finish(&self, phase: Option<Part>) {
+        self.running.lock().unwrap().retain(|&(p, _)| p != phase);
+    }
+
+    /// The first phase whose current run has taken longer than `timeout`
+    fn overdue(&self, timeout: Duration) -> Option<Option<Part>> {
+        let running = self.running.lock().unwrap();
+        running
+            .iter()
+            .find(|(_, started)| started.elapsed() > timeout)
+            .map(|&(phase, _)| phase)
+    }
+}
+
+thread_local! {
+    static PROGRESS: RefCell<Option<Arc<Progress>>> = const { RefCell::new(None) };
+}
+
+/// The progress tracker of the current thread, to hand to threads it spawns
+pub(crate) fn current() -> Option<Arc<Progress>> {
+    PROGRESS.with_borrow(Clone::clone)
+}
+
+pub(crate) fn set_current(progress: Option<Arc<Progress>>) {
+    PROGRESS.set(progress);
+}
+
+/// Marks the start of a single run of a phase on this thread
+pub(crate) fn start(phase: Option<Part>) {
+    PROGRESS.with_borrow(|progress| {
+        if let Some(progress) = progress {
+            progress.start(phase);
+        }
+    });
+}
+
+pub(crate) fn finish(phase: Option<Part>) {
+    PROGRESS.with_borrow(|progress| {
+        if let Some(progress) = progress {
+            progress.finish(phase);
+        }
+    });
+}
+
+/// Runs the day on a new thread, giving up once a single run of any phase takes longer than
+/// `timeout`
+pub(crate) fn run(
+    year: u16,
+    day_num: usize,
+    run: DayFn,
+    input: &str,
+    options: &RunOptions,
+    timeout: Duration,
+) -> Result<DayResults, RunnerError> {
+    let progress = Arc::new(Progress::default());
+    let (tx, rx) = mpsc::channel();
+    let (input, options) = (input.to_owned(), options.clone());
+    let thread_progress = Arc::clone(&progress);
+    thread::Builder::new()
+        .name(format!("day{day_num}"))
+        .spawn(move || {
+            set_current(Some(thread_progress));
+            // The receiver is gone if the day already timed out
+            let _ = tx.send(crate::catch_panic(year, day_num, run, &input, &options));
+        })
+        .expect("failed to spawn a thread for the day");
+
+    let poll_interval = (timeout / 10).clamp(Duration::from_millis(1), Duration::from_millis(50));
+    loop {
+        match rx.recv_timeout(poll_interval) {
+            Ok(result) => return result,
+            Err(RecvTimeoutError::Timeout) => {
+                if let Some(part) = progress.overdue(timeout) {
+                    return Err(RunnerError::Timeout {
+                        year,
+                        day: day_num,
+                        part,
+                        timeout,
+                    });
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(RunnerError::SolverFailure {
+                    year,
+                    day: day_num,
+                    message: "the thread running the day exited without a result".to_owned(),
+                })
+            }
+        }
+    }
+}
+
+#[test]
+fn hung_part_times_out() {
+    fn hang(_input: &str, _options: &RunOptions) -> Result<DayResults, crate::ParseError> {
+        start(Some(Part::Two));
+        loop {
+            thread::park();
+        }
+    }
+
+    let timeout = Duration::from_millis(20);
+    let result = run(2024, 6, hang, "", &RunOptions::default(), timeout);
+    assert!(matches!(
+        result,
+        Err(RunnerError::Timeout {
+            part: Some(Part::Two),
+            ..
+        })
+    ));
+}
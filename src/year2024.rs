@@ -0,0 +1,9 @@
+crate::register_days! {
+    1 => day1::Day1,
+    2 => day2::Day2,
+    3 => day3::Day3,
+    4 => day4::Day4,
+    5 => day5::Day5,
+    6 => day6::Day6,
+    9 => day9::Day9,
+}
@@ -0,0 +1,166 @@
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
+
+pub struct Day1;
+
+/// Each left value is at most `u32::MAX`, and can match every one of the lines on the right, so
+/// with at most 2^16 lines the similarity score fits in a `u64`
+const MAX_LINES: usize = 1 << 16;
+
+impl Day for Day1 {
+    type Parsed<'a> = (Vec<u32>, Vec<u32>);
+
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("11"),
+            part2: Some("31"),
+        },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Parsed<'_>, ParseError> {
+        let err = |offset, message: &str| ParseError::new(input, offset, message);
+        let lines = input.bytes().filter(|&c| c == b'\n').count();
+        let mut l = Vec::with_capacity(lines);
+        let mut r = Vec::with_capacity(lines);
+        let mut current_val: u32 = 0;
+        let mut digits = 0;
+        let mut has_val = true;
+        for (i, b) in crate::with_final_newline(input.as_bytes()).enumerate() {
+            match b {
+                b' ' => {
+                    if has_val {
+                        if digits == 0 {
+                            return Err(err(i, "expected a number"));
+                        }
+                        l.push(current_val);
+                        has_val = false;
+                        current_val = 0;
+                        digits = 0;
+                    } else if digits != 0 {
+                        return Err(err(i, "expected a newline after the second number"));
+                    }
+                }
+                b'\n' => {
+                    if has_val || digits == 0 {
+                        return Err(err(i, "expected two numbers separated by spaces"));
+                    }
+                    r.push(current_val);
+                    current_val = 0;
+                    digits = 0;
+                    has_val = true;
+                }
+                b'0'..=b'9' => {
+                    current_val = current_val
+                        .checked_mul(10)
+                        .and_then(|val| val.checked_add(u32::from(b - b'0')))
+                        .ok_or_else(|| err(i, "number is too large"))?;
+                    digits += 1;
+                }
+                _ => return Err(err(i, "expected a digit, space or newline")),
+            }
+        }
+        l.sort_unstable();
+        r.sort_unstable();
+        Ok((l, r))
+    }
+
+    fn validate(input: &str, part: Option<Part>) -> Vec<ParseError> {
+        let mut violations = Vec::new();
+        // Only the similarity score of part 2 can overflow
+        if part == Some(Part::One) {
+            return violations;
+        }
+        if let Some(line) = input.lines().nth(MAX_LINES) {
+            let lines = input.lines().count();
+            violations.push(ParseError::at(
+                input,
+                line,
+                format!("there are {lines} lines, but at most {MAX_LINES} are supported"),
+            ));
+        }
+        violations
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // Real inputs have 1000 lines
+        let lines = crate::scaled(1000, scale).min(MAX_LINES);
+        // Left values are distinct, and some appear on the right
+        let mut left: Vec<u64> = (10_000..=99_999).collect();
+        rng.shuffle(&mut left);
+        left.truncate(lines);
+        let mut input = String::with_capacity(lines * 14);
+        for &l in &left {
+            let r = if rng.chance(1, 4) {
+                left[rng.range(0..=lines as u64 - 1) as usize]
+            } else {
+                rng.range(10_000..=99_999)
+            };
+            input.push_str(&format!("{l}   {r}\n"));
+        }
+        Some(input)
+    }
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let (l, r) = input;
+        let mut total_diff = 0;
+        for (&l, &r) in l.iter().zip(r.iter()) {
+            total_diff += u64::from(l.abs_diff(r));
+        }
+        total_diff
+    }
+
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let (l, r) = input;
+        let mut remaining_r = &r[..];
+        let mut similarity = 0;
+        for l in l.iter().copied() {
+            // Faster to just linear search rather than binary search for AOC input sizes
+            let start = remaining_r
+                .iter()
+                .position(|&r| r >= l)
+                .unwrap_or(remaining_r.len());
+            remaining_r = &remaining_r[start..];
+
+            let count = remaining_r
+                .iter()
+                .position(|&r| r != l)
+                .unwrap_or(remaining_r.len());
+            // Keep the run, the next left value may be the same
+            similarity += u64::from(l) * count as u64;
+        }
+
+        similarity
+    }
+}
+
+const EXAMPLE: &str = "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+crate::codspeed_def!(Day1);
+
+#[test]
+fn malformed_input() {
+    let err = Day1::generator("3   4\n4   3\n2   x\n").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 5));
+    assert!(Day1::generator("3   4\n4\n").is_err());
+    assert!(Day1::generator("3   4   5\n").is_err());
+    assert_eq!(Day1::generator("3   4").unwrap(), (vec![3], vec![4]));
+}
+
+#[test]
+fn validate_line_count() {
+    assert!(Day1::validate(EXAMPLE, None).is_empty());
+    let too_many_lines = "3   4\n".repeat(MAX_LINES + 1);
+    let violations = Day1::validate(&too_many_lines, Some(Part::Two));
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].line, MAX_LINES + 1);
+    assert!(Day1::validate(&too_many_lines, Some(Part::One)).is_empty());
+}
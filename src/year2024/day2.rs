@@ -0,0 +1,254 @@
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
+use arrayvec::ArrayVec;
+
+pub struct Day2;
+
+type Int = u8;
+const MAX_VALUES_PER_REPORT: usize = 16;
+type Report = ArrayVec<Int, MAX_VALUES_PER_REPORT>;
+
+fn is_safe_report(report: &[Int]) -> bool {
+    let (&[x, y], rest) = report.split_first_chunk().unwrap();
+    let increasing = y > x;
+    if !(1..4).contains(&x.abs_diff(y)) {
+        return false;
+    }
+    first_unsafety(y, increasing, rest).is_none()
+}
+
+fn first_unsafety(prev_value: Int, increasing: bool, remaining_report: &[Int]) -> Option<usize> {
+    let range = 1..4;
+    let mut last = prev_value;
+    for (i, &v) in remaining_report.iter().enumerate() {
+        let diff = if increasing {
+            v.wrapping_sub(last)
+        } else {
+            last.wrapping_sub(v)
+        };
+        if !range.contains(&diff) {
+            return Some(i);
+        }
+        last = v;
+    }
+    None
+}
+
+impl Day for Day2 {
+    type Parsed<'a> = Vec<Report>;
+
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("2"),
+            part2: Some("4"),
+        },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Parsed<'_>, ParseError> {
+        let err = |offset, message: &str| ParseError::new(input, offset, message);
+        let mut dst = Vec::with_capacity(2000);
+        let mut current = ArrayVec::new();
+        let mut current_val: Int = 0;
+        let mut digits = 0;
+        for (i, b) in crate::with_final_newline(input.as_bytes()).enumerate() {
+            match b {
+                b' ' | b'\n' => {
+                    if digits == 0 {
+                        return Err(err(i, "expected a level"));
+                    }
+                    current
+                        .try_push(current_val)
+                        .map_err(|_| err(i, "too many levels in one report"))?;
+                    current_val = 0;
+                    digits = 0;
+                    if b == b'\n' {
+                        if current.len() < 2 {
+                            return Err(err(i, "a report needs at least two levels"));
+                        }
+                        dst.push(current);
+                        current = ArrayVec::new();
+                    }
+                }
+                b'0'..=b'9' => {
+                    current_val = current_val
+                        .checked_mul(10)
+                        .and_then(|val| val.checked_add(b - b'0'))
+                        .ok_or_else(|| err(i, "level is too large"))?;
+                    digits += 1;
+                }
+                _ => return Err(err(i, "expected a digit, space or newline")),
+            }
+        }
+        Ok(dst)
+    }
+
+    fn validate(input: &str, _part: Option<Part>) -> Vec<ParseError> {
+        let mut violations = Vec::new();
+        for report in input.lines() {
+            let levels: Vec<_> = report.split_ascii_whitespace().collect();
+            if levels.len() > MAX_VALUES_PER_REPORT {
+                violations.push(ParseError::at(
+                    input,
+                    levels[MAX_VALUES_PER_REPORT],
+                    format!(
+                        "a report has {} levels, but at most {MAX_VALUES_PER_REPORT} are supported",
+                        levels.len()
+                    ),
+                ));
+            }
+            for level in levels {
+                let is_number = level.bytes().all(|b| b.is_ascii_digit());
+                if is_number && level.parse::<Int>().is_err() {
+                    violations.push(ParseError::at(
+                        input,
+                        level,
+                        format!("level {level} is larger than the maximum of {}", Int::MAX),
+                    ));
+                }
+            }
+        }
+        violations
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // Real inputs have 1000 reports of 5 to 8 levels, mostly safe steps with the occasional
+        // bad one so many are nearly safe
+        let mut input = String::new();
+        for _ in 0..crate::scaled(1000, scale) {
+            let increasing = rng.chance(1, 2);
+            let mut level = rng.range(0..=Int::MAX.into()) as i32;
+            let mut levels = vec![level.to_string()];
+            let len = if rng.chance(1, 10) {
+                rng.range(2..=MAX_VALUES_PER_REPORT as u64)
+            } else {
+                rng.range(5..=8)
+            };
+            for _ in 1..len {
+                let step = if rng.chance(9, 10) {
+                    rng.range(1..=3) as i32
+                } else {
+                    rng.range(0..=8) as i32 - 4
+                };
+                level = (if increasing { level + step } else { level - step })
+                    .clamp(0, Int::MAX.into());
+                levels.push(level.to_string());
+            }
+            input.push_str(&levels.join(" "));
+            input.push('\n');
+        }
+        Some(input)
+    }
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        input
+            .iter()
+            .filter(|&report| is_safe_report(report))
+            .count()
+    }
+
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        input.iter().filter(|&report| can_be_safe(report)).count()
+    }
+}
+
+fn can_be_safe(report: &[Int]) -> bool {
+    let (&[x, y], rest) = report.split_first_chunk().unwrap();
+    if !(1..4).contains(&x.abs_diff(y)) {
+        // Dropping either level of a two level report leaves a safe one
+        let Some((&z, rest)) = rest.split_first() else {
+            return true;
+        };
+        // try omitting x:
+        {
+            let increasing = z > y;
+            if (1..4).contains(&y.abs_diff(z)) && first_unsafety(z, increasing, rest).is_none() {
+                return true;
+            }
+        }
+        // try omitting y
+        {
+            let increasing = z > x;
+            if (1..4).contains(&x.abs_diff(z)) && first_unsafety(z, increasing, rest).is_none() {
+                return true;
+            }
+        }
+        return false;
+    }
+    let increasing = y > x;
+    let Some(i) = first_unsafety(y, increasing, rest) else {
+        return true;
+    };
+    let i = i + 2;
+    // If we get an error at 2, also try removing the first value
+    if i == 2 {
+        let (&z, rest) = rest.split_first().unwrap();
+        let increasing = z > y;
+        if (1..4).contains(&y.abs_diff(z)) && first_unsafety(z, increasing, rest).is_none() {
+            return true;
+        }
+    }
+    // Try omitting the previous value
+    {
+        let last_value = report[i - 2];
+        let increasing = if i == 2 {
+            // Need to recalculate if we're increasing if the previous value is y
+            report[i] > last_value
+        } else {
+            increasing
+        };
+        if first_unsafety(last_value, increasing, &report[i..]).is_none() {
+            return true;
+        }
+    }
+    // Try omitting the value
+    {
+        let last_value = report[i - 1];
+        if first_unsafety(last_value, increasing, &report[i + 1..]).is_none() {
+            return true;
+        }
+    }
+    false
+}
+
+const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+crate::codspeed_def!(Day2);
+
+/// Checks every report the slow way, removing each level in turn for part 2
+#[cfg(test)]
+fn reference(input: &str) -> (usize, usize) {
+    let is_safe = |report: &[i32]| {
+        let diffs: Vec<i32> = report.windows(2).map(|w| w[1] - w[0]).collect();
+        diffs.iter().all(|d| (1..=3).contains(d)) || diffs.iter().all(|d| (-3..=-1).contains(d))
+    };
+    let reports: Vec<Vec<i32>> = input
+        .lines()
+        .map(|line| line.split(' ').map(|level| level.parse().unwrap()).collect())
+        .collect();
+    let part1 = reports.iter().filter(|report| is_safe(report)).count();
+    let part2 = reports
+        .iter()
+        .filter(|report| {
+            (0..report.len()).any(|skip| {
+                let mut report = report.to_vec();
+                report.remove(skip);
+                is_safe(&report)
+            })
+        })
+        .count();
+    (part1, part2)
+}
+
+#[test]
+fn matches_reference() {
+    crate::check_against_reference::<Day2, _, _>(5000, 0.02, reference);
+}
@@ -0,0 +1,139 @@
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
+use memchr::memmem;
+
+pub struct Day3;
+
+type Int = u32;
+
+impl Day for Day3 {
+    type Parsed<'a> = &'a str;
+
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE_PART1,
+            part1: Some("161"),
+            part2: None,
+        },
+        Example {
+            input: EXAMPLE_PART2,
+            part1: None,
+            part2: Some("48"),
+        },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Parsed<'_>, ParseError> {
+        // Corrupted memory, anything goes
+        Ok(input)
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // No digits or parentheses, so noise can't complete an instruction
+        const NOISE: &[u8] = b"abdmlotu !@#$%^&*+-=[]{}<>?/;:',_~";
+        let len = crate::scaled(18_000, scale);
+        let mut input = String::with_capacity(len + 64);
+        while input.len() < len {
+            match rng.range(0..=19) {
+                0..=2 => {
+                    let (x, y) = (rng.range(1..=999), rng.range(1..=999));
+                    input.push_str(&format!("mul({x},{y})"));
+                }
+                3 => {
+                    let broken = ["mul(4*", "mul[3,7]", "mul ( 2 , 4 )", "mul(6,9!", "mul(32,64]"];
+                    input.push_str(broken[rng.range(0..=4) as usize]);
+                }
+                4 => input.push_str(if rng.chance(1, 2) { "do()" } else { "don't()" }),
+                5 if rng.chance(1, 50) => input.push('\n'),
+                _ => input.push(char::from(NOISE[rng.range(0..=NOISE.len() as u64 - 1) as usize])),
+            }
+        }
+        input.push('\n');
+        Some(input)
+    }
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let input = input.as_bytes();
+        let mult_finder = memmem::Finder::new("mul(");
+        let mut result: u64 = 0;
+        for mul_idx in mult_finder.find_iter(input) {
+            if let Some((x, y)) =
+                parse_after_mul(&mut &input[mul_idx + mult_finder.needle().len()..])
+            {
+                result += u64::from(x * y);
+            }
+        }
+        result
+    }
+
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let mut input = input.as_bytes();
+        let do_finder = memmem::Finder::new("do()");
+        let dont_finder = memmem::Finder::new("don't()");
+        let mult_finder = memmem::Finder::new("mul(");
+
+        let mut result: u64 = 0;
+        loop {
+            let next_dont = dont_finder.find(input).unwrap_or(input.len());
+            let (mut inner_input, rest) = (
+                &input[..next_dont],
+                input
+                    .get(next_dont + dont_finder.needle().len()..)
+                    .unwrap_or(&[]),
+            );
+            while let Some(next_mul) = mult_finder.find(inner_input) {
+                inner_input = &inner_input[next_mul + mult_finder.needle().len()..];
+                if let Some((x, y)) = parse_after_mul(&mut inner_input) {
+                    result += u64::from(x * y);
+                }
+            }
+            input = rest;
+            let Some(next_do) = do_finder.find(input) else {
+                break;
+            };
+            input = &input[next_do + do_finder.needle().len()..];
+        }
+
+        result
+    }
+}
+
+/// Parses `X,Y)` where `X` and `Y` are 1-3 digits, only advancing `input` if it matched
+fn parse_after_mul(input: &mut &[u8]) -> Option<(Int, Int)> {
+    let mut rest = *input;
+    let x = parse_number(&mut rest, b',')?;
+    let y = parse_number(&mut rest, b')')?;
+    *input = rest;
+    Some((x, y))
+}
+
+fn parse_number(input: &mut &[u8], terminator: u8) -> Option<Int> {
+    let digits = input
+        .iter()
+        .take(3)
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if digits == 0 || input.get(digits) != Some(&terminator) {
+        return None;
+    }
+    let n = input[..digits]
+        .iter()
+        .fold(0, |n, &b| n * 10 + Int::from(b - b'0'));
+    *input = &input[digits + 1..];
+    Some(n)
+}
+
+const EXAMPLE_PART1: &str =
+    "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+const EXAMPLE_PART2: &str =
+    "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+crate::codspeed_def!(Day3);
+
+#[test]
+fn malformed_instructions() {
+    let answer = |input: &'static str| Day3::part2(input).into();
+    assert_eq!(answer("mul(1234,5)mul(,4)mul(2,3"), Answer::Int(0));
+    assert_eq!(answer("mul(2,mul(3,4))"), Answer::Int(12));
+    assert_eq!(Day3::part1("mul(999,999)").into(), Answer::Int(998_001));
+}
@@ -1,10 +1,9 @@
-use crate::Day;
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
 use memchr::{memchr, memchr_iter};
-use std::fmt::Display;
 
 pub struct Day4;
 
-type Int = u32;
+type Int = usize;
 
 #[derive(Debug, Clone)]
 pub struct Grid<'a> {
@@ -88,13 +87,54 @@ impl Grid<'_> {
 impl Day for Day4 {
     type Parsed<'a> = Grid<'a>;
 
-    fn generator(input: &str) -> Self::Parsed<'_> {
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("18"),
+            part2: Some("9"),
+        },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Parsed<'_>, ParseError> {
         let data = input.as_bytes();
-        let width = memchr(b'\n', data).unwrap();
-        Grid { width, data }
+        let row_err = |offset, width| {
+            ParseError::new(input, offset, format!("expected every row to be {width} letters"))
+        };
+        let width = memchr(b'\n', data)
+            .ok_or_else(|| ParseError::new(input, data.len(), "expected a newline"))?;
+        if width == 0 {
+            return Err(ParseError::new(input, 0, "expected a row of letters"));
+        }
+        let mut expected_newline = width;
+        for newline in memchr_iter(b'\n', data) {
+            if newline != expected_newline {
+                return Err(row_err(newline, width));
+            }
+            expected_newline += width + 1;
+        }
+        // The last row may be missing its newline
+        if data.len() > expected_newline - width && data.len() != expected_newline {
+            return Err(row_err(data.len(), width));
+        }
+        Ok(Grid { width, data })
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // Real inputs are 140x140
+        let side = crate::scaled(140, scale.sqrt());
+        let mut input = String::with_capacity(side * (side + 1));
+        for _ in 0..side {
+            for _ in 0..side {
+                input.push(char::from(b"XMAS"[rng.range(0..=3) as usize]));
+            }
+            input.push('\n');
+        }
+        Some(input)
     }
 
-    fn part1(input: Self::Parsed<'_>) -> impl Display {
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
         let mut xmas_count = 0;
         for offset in memchr_iter(b'X', input.data) {
             for dx in -1..2 {
@@ -109,7 +149,7 @@ impl Day for Day4 {
         xmas_count
     }
 
-    fn part2(input: Self::Parsed<'_>) -> impl Display {
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
         let mut xmas_count = 0;
         for offset in memchr_iter(b'A', input.data) {
             xmas_count += Int::from(input.has_mas_x_at(offset));
@@ -118,4 +158,17 @@ impl Day for Day4 {
     }
 }
 
+const EXAMPLE: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
 crate::codspeed_def!(Day4);
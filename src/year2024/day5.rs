@@ -0,0 +1,521 @@
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
+use arrayvec::ArrayVec;
+use core::fmt;
+use std::collections::HashSet;
+
+pub struct Day5;
+
+type Int = u8;
+type BitsetWord = u64;
+// TODO: Try using Int::MAX to avoid bounds checks
+const MAX_INT: Int = 99;
+const BITSET_LEN_WORDS: usize = (MAX_INT as usize + 1).div_ceil(BitsetWord::BITS as usize);
+const MAX_UPDATE_SIZE: usize = 64;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct Bitset([BitsetWord; BITSET_LEN_WORDS]);
+
+impl Bitset {
+    const fn new() -> Self {
+        Self([0; BITSET_LEN_WORDS])
+    }
+
+    fn set(&mut self, n: Int) {
+        let (idx, bit) = (
+            n as usize / BitsetWord::BITS as usize,
+            n as usize % BitsetWord::BITS as usize,
+        );
+        self.0[idx] |= 1 << bit;
+    }
+
+    fn get(&self, n: Int) -> bool {
+        let (idx, bit) = (
+            n as usize / BitsetWord::BITS as usize,
+            n as usize % BitsetWord::BITS as usize,
+        );
+        self.0[idx] & (1 << bit) != 0
+    }
+
+    pub fn remove(&mut self, i: Int) {
+        let (idx, bit) = (
+            i as usize / BitsetWord::BITS as usize,
+            i as usize % BitsetWord::BITS as usize,
+        );
+        self.0[idx] &= !(1 << bit);
+    }
+
+    fn first_set(&self) -> Option<Int> {
+        for (i, &word) in self.0.iter().enumerate() {
+            if word != 0 {
+                let bit = word.trailing_zeros();
+                return Some((i * BitsetWord::BITS as usize + bit as usize) as Int);
+            }
+        }
+        None
+    }
+
+    fn intersects_any(&self, other: &Self) -> bool {
+        for (lhs, &rhs) in self.0.iter().zip(other.0.iter()) {
+            if lhs & rhs != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn remove_all(&mut self, other: &Self) {
+        for (lhs, &rhs) in self.0.iter_mut().zip(other.0.iter()) {
+            *lhs &= !rhs;
+        }
+    }
+
+    fn count_ones(&self) -> usize {
+        self.0.iter().map(|&word| word.count_ones() as usize).sum()
+    }
+}
+
+impl fmt::Debug for Bitset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..=MAX_INT).filter(|&i| self.get(i)))
+            .finish()
+    }
+}
+
+impl std::ops::BitAnd for Bitset {
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self {
+        for (lhs, &rhs) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *lhs &= rhs;
+        }
+        self
+    }
+}
+
+impl std::ops::BitOrAssign for Bitset {
+    fn bitor_assign(&mut self, rhs: Self) {
+        for (lhs, &rhs) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *lhs |= rhs;
+        }
+    }
+}
+
+/// [i] is a bitset of all the numbers that must come after i
+#[derive(Clone, Debug)]
+struct RequiredAfter([Bitset; MAX_INT as usize + 1]);
+
+impl RequiredAfter {
+    const fn new() -> Self {
+        Self([Bitset::new(); MAX_INT as usize + 1])
+    }
+
+    fn add_require(&mut self, i: Int, required: Int) {
+        self.0[i as usize].set(required);
+    }
+
+    fn requirements_of(&self, i: Int) -> &Bitset {
+        &self.0[i as usize]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Input {
+    required_after: RequiredAfter,
+    updates: Vec<ArrayVec<Int, MAX_UPDATE_SIZE>>,
+}
+
+impl Day for Day5 {
+    type Parsed<'a> = Input;
+
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("143"),
+            part2: Some("123"),
+        },
+    ];
+
+    fn generator(text: &str) -> Result<Self::Parsed<'_>, ParseError> {
+        let err = |offset, message: &str| ParseError::new(text, offset, message);
+        let input = text.as_bytes();
+
+        let mut result = Input {
+            required_after: RequiredAfter::new(),
+            updates: Vec::with_capacity(256),
+        };
+
+        let mut end = None;
+        for (i, chunk) in input.chunks_exact(6).enumerate() {
+            let [a, b, pipe, c, d, newline] = chunk.try_into().unwrap();
+            if a == b'\n' {
+                end = Some(i * 6);
+                break;
+            }
+            let bad_byte = [a, b, pipe, c, d, newline]
+                .iter()
+                .zip(b"00|00\n")
+                .position(|(&actual, &expected)| match expected {
+                    b'0' => !actual.is_ascii_digit(),
+                    _ => actual != expected,
+                });
+            if let Some(j) = bad_byte {
+                return Err(err(i * 6 + j, "expected a rule like `47|53`"));
+            }
+
+            let first = (a - b'0') * 10 + (b - b'0');
+            let second = (c - b'0') * 10 + (d - b'0');
+            result.required_after.add_require(first, second);
+        }
+        // The blank line may be in the last, incomplete chunk
+        let rules_end = input.len() - input.len() % 6;
+        let end = end
+            .or_else(|| (input.get(rules_end) == Some(&b'\n')).then_some(rules_end))
+            .ok_or_else(|| err(rules_end, "expected a rule like `47|53` or a blank line"))?;
+
+        let mut current_val: Int = 0;
+        let mut digits = 0;
+        let mut current = ArrayVec::new();
+        for (i, b) in crate::with_final_newline(&input[end + 1..]).enumerate() {
+            let offset = end + 1 + i;
+            match b {
+                b',' | b'\n' => {
+                    if digits == 0 {
+                        return Err(err(offset, "expected a page number"));
+                    }
+                    current
+                        .try_push(current_val)
+                        .map_err(|_| err(offset, "too many pages in one update"))?;
+                    current_val = 0;
+                    digits = 0;
+                    if b == b'\n' {
+                        result.updates.push(current);
+                        current = ArrayVec::new();
+                    }
+                }
+                b'0'..=b'9' => {
+                    current_val = current_val
+                        .checked_mul(10)
+                        .and_then(|val| val.checked_add(b - b'0'))
+                        .filter(|&val| val <= MAX_INT)
+                        .ok_or_else(|| err(offset, "page number is too large"))?;
+                    digits += 1;
+                }
+                _ => return Err(err(offset, "expected a digit, comma or newline")),
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn validate(input: &str, part: Option<Part>) -> Vec<ParseError> {
+        let mut violations = Vec::new();
+        let (rules, updates) = input.split_once("\n\n").unwrap_or((input, ""));
+        let mut check_page = |page: &str| {
+            let is_number = !page.is_empty() && page.bytes().all(|b| b.is_ascii_digit());
+            if is_number && page.parse::<usize>().is_ok_and(|page| page > MAX_INT.into()) {
+                let message = format!("page {page} is larger than the maximum of {MAX_INT}");
+                violations.push(ParseError::at(input, page, message));
+            }
+        };
+        for rule in rules.lines() {
+            rule.split('|').for_each(&mut check_page);
+        }
+        for update in updates.lines() {
+            update.split(',').for_each(&mut check_page);
+        }
+
+        let rules: HashSet<(Int, Int)> = rules
+            .lines()
+            .filter_map(|rule| {
+                let (before, after) = rule.split_once('|')?;
+                Some((before.parse().ok()?, after.parse().ok()?))
+            })
+            .collect();
+        for update in updates.lines() {
+            let pages = update.split(',').count();
+            if pages > MAX_UPDATE_SIZE {
+                let message =
+                    format!("an update has {pages} pages, but at most {MAX_UPDATE_SIZE} are supported");
+                violations.push(ParseError::at(input, update, message));
+            }
+            if pages % 2 == 0 {
+                let message = format!("an update has {pages} pages, so it has no middle page");
+                violations.push(ParseError::at(input, update, message));
+            }
+            if part == Some(Part::One) {
+                continue;
+            }
+            let pages: Option<Vec<Int>> = update.split(',').map(|page| page.parse().ok()).collect();
+            if let Some(message) = pages.and_then(|pages| ordering_violation(&rules, &pages)) {
+                violations.push(ParseError::at(input, update, message));
+            }
+        }
+        violations
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // Real inputs have a rule for every pair of 49 pages in a random order, so each update
+        // has exactly one sorted order, and 200 updates of up to 23 pages
+        let mut order: Vec<Int> = (10..=MAX_INT).collect();
+        rng.shuffle(&mut order);
+        order.truncate(crate::scaled(49, scale.sqrt()).clamp(2, 49));
+        let mut rules = Vec::with_capacity(order.len() * order.len() / 2);
+        for (i, &before) in order.iter().enumerate() {
+            for &after in &order[i + 1..] {
+                rules.push(format!("{before}|{after}\n"));
+            }
+        }
+        rng.shuffle(&mut rules);
+        let mut input = rules.concat();
+        input.push('\n');
+
+        let max_half_len = ((order.len() as u64 - 1) / 2).min(11);
+        for _ in 0..crate::scaled(200, scale) {
+            let mut update = order.clone();
+            rng.shuffle(&mut update);
+            update.truncate(rng.range(0..=max_half_len) as usize * 2 + 1);
+            if rng.chance(1, 2) {
+                update.sort_by_key(|page| order.iter().position(|p| p == page));
+            }
+            let pages: Vec<String> = update.iter().map(ToString::to_string).collect();
+            input.push_str(&pages.join(","));
+            input.push('\n');
+        }
+        Some(input)
+    }
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let mut result: u64 = 0;
+        for update in &input.updates {
+            if !is_valid_update(&input.required_after, update) {
+                continue;
+            }
+            result += u64::from(update[update.len() / 2]);
+        }
+        result
+    }
+
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let mut result: u64 = 0;
+        for update in &input.updates {
+            let mut seen_bitset = Bitset::new();
+            let mut valid = true;
+            for &val in update {
+                if seen_bitset.intersects_any(input.required_after.requirements_of(val)) {
+                    valid = false;
+                }
+                seen_bitset.set(val);
+            }
+            if valid {
+                continue;
+            }
+            let recursive_requires = create_recursive_requires(&input.required_after, seen_bitset);
+            // Without exactly one sorted order there's no middle page, see `ordering_violation`
+            let Some(sorted_midpoint) = update.iter().copied().find(|&val| {
+                recursive_requires.requirements_of(val).count_ones() == update.len() / 2
+            }) else {
+                continue;
+            };
+            result += u64::from(sorted_midpoint);
+        }
+        result
+    }
+}
+
+/// Part 2 needs the rules to put the pages of every update in exactly one order
+fn ordering_violation(rules: &HashSet<(Int, Int)>, pages: &[Int]) -> Option<String> {
+    let mut later_pages = Vec::with_capacity(pages.len());
+    for (i, &a) in pages.iter().enumerate() {
+        if rules.contains(&(a, a)) {
+            return Some(format!("a rule orders page {a} before itself"));
+        }
+        let mut later = 0;
+        for &b in &pages[i + 1..] {
+            match (rules.contains(&(a, b)), rules.contains(&(b, a))) {
+                _ if a == b => return Some(format!("page {a} appears more than once in an update")),
+                (false, false) => return Some(format!("no rule orders pages {a} and {b}")),
+                (true, true) => return Some(format!("rules order pages {a} and {b} both ways")),
+                (true, false) => later += 1,
+                (false, true) => {}
+            }
+        }
+        later += pages[..i].iter().filter(|&&b| rules.contains(&(a, b))).count();
+        later_pages.push(later);
+    }
+    // Without a cycle, each page has a different number of pages that must come after it
+    later_pages.sort_unstable();
+    later_pages.dedup();
+    (later_pages.len() != pages.len())
+        .then(|| "the rules for the pages of an update contain a cycle".to_owned())
+}
+
+fn is_valid_update(required_after: &RequiredAfter, update: &[Int]) -> bool {
+    let mut seen_bitset = Bitset::new();
+    for &val in update {
+        if seen_bitset.intersects_any(&required_after.0[val as usize]) {
+            return false;
+        }
+        seen_bitset.set(val);
+    }
+    true
+}
+
+fn create_recursive_requires(required_after: &RequiredAfter, mask: Bitset) -> RequiredAfter {
+    let mut result = RequiredAfter::new();
+    let mut present_in_recursive = Bitset::new();
+
+    let mut mask_iter = mask;
+    while let Some(i) = mask_iter.first_set() {
+        recursive_requires(
+            required_after,
+            &mut present_in_recursive,
+            &mut result,
+            mask,
+            i,
+        );
+        mask_iter.remove(i);
+    }
+
+    result
+}
+
+fn recursive_requires(
+    required_after: &RequiredAfter,
+    present_in_recursive: &mut Bitset,
+    recursive_req: &mut RequiredAfter,
+    mask: Bitset,
+    i: Int,
+) -> Bitset {
+    if present_in_recursive.get(i) {
+        return *recursive_req.requirements_of(i);
+    }
+    let mut requirements = *required_after.requirements_of(i) & mask;
+    let mut result = requirements;
+    present_in_recursive.set(i);
+    while let Some(j) = requirements.first_set() {
+        let sub_requirements =
+            recursive_requires(required_after, present_in_recursive, recursive_req, mask, j);
+        result |= sub_requirements;
+        requirements.remove_all(&sub_requirements);
+        requirements.remove(j);
+    }
+    recursive_req.0[i as usize] = result;
+    result
+}
+
+const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+crate::codspeed_def!(Day5);
+
+#[test]
+fn malformed_input() {
+    let err = Day5::generator("47|53\n97-13\n\n75,47,61\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 3));
+    let err = Day5::generator("47|53\n\n75,147,61\n").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 6));
+    assert!(Day5::generator("47|53\n97|13\n").is_err());
+    // A missing final newline is fine
+    let parsed = Day5::generator("47|53\n\n75,47,53").unwrap();
+    assert_eq!(parsed.updates.len(), 1);
+}
+
+/// Checks every pair of pages against the rules, and sorts with the rules as the comparator
+#[cfg(test)]
+fn reference(input: &str) -> (u32, u32) {
+    let (rules, updates) = input.split_once("\n\n").unwrap();
+    let rules: std::collections::HashSet<(u32, u32)> = rules
+        .lines()
+        .map(|rule| {
+            let (before, after) = rule.split_once('|').unwrap();
+            (before.parse().unwrap(), after.parse().unwrap())
+        })
+        .collect();
+    let (mut part1, mut part2) = (0, 0);
+    for update in updates.lines() {
+        let mut pages: Vec<u32> = update.split(',').map(|page| page.parse().unwrap()).collect();
+        let in_order = (0..pages.len())
+            .all(|i| (i + 1..pages.len()).all(|j| !rules.contains(&(pages[j], pages[i]))));
+        if in_order {
+            part1 += pages[pages.len() / 2];
+        } else {
+            pages.sort_by(|&a, &b| {
+                if rules.contains(&(a, b)) {
+                    std::cmp::Ordering::Less
+                } else if rules.contains(&(b, a)) {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            });
+            part2 += pages[pages.len() / 2];
+        }
+    }
+    (part1, part2)
+}
+
+#[test]
+fn matches_reference() {
+    crate::check_against_reference::<Day5, _, _>(2000, 0.2, reference);
+}
+
+#[test]
+fn validate_ordering() {
+    assert!(Day5::validate("47|53\n53|61\n47|61\n\n47,61,53\n", None).is_empty());
+    let messages = |input| {
+        Day5::validate(input, None)
+            .into_iter()
+            .map(|violation| violation.message)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages("47|53\n\n47,53,47\n"),
+        ["page 47 appears more than once in an update"]
+    );
+    assert_eq!(
+        messages("47|53\n53|61\n\n47,61,53\n"),
+        ["no rule orders pages 47 and 61"]
+    );
+    // Part 1 only checks the rules that exist
+    assert!(Day5::validate("47|53\n53|61\n\n47,61,53\n", Some(Part::One)).is_empty());
+    assert_eq!(
+        messages("47|53\n53|61\n61|47\n\n47,61,53\n"),
+        ["the rules for the pages of an update contain a cycle"]
+    );
+    let self_rule = "10|10\n13|12\n10|14\n12|14\n12|10\n\n10,14,12\n";
+    assert_eq!(messages(self_rule), ["a rule orders page 10 before itself"]);
+    // Part 2 skips an update without a middle page instead of panicking
+    let parsed = Day5::generator(self_rule).unwrap();
+    assert_eq!(Day5::part2(parsed).into(), Answer::from(0_u64));
+}
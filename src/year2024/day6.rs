@@ -0,0 +1,420 @@
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
+use arrayvec::ArrayVec;
+
+pub struct Day6;
+
+const MAX_DIM: usize = 150;
+type BitsetWord = u64;
+const BITSET_LEN_BITS: usize = MAX_DIM * MAX_DIM;
+const BITSET_LEN_WORDS: usize = BITSET_LEN_BITS.div_ceil(BitsetWord::BITS as usize);
+
+#[derive(Debug, Clone)]
+struct Bitset(ArrayVec<BitsetWord, BITSET_LEN_WORDS>);
+
+impl Bitset {
+    fn set(&mut self, i: usize) {
+        let (idx, bit) = (i / BitsetWord::BITS as usize, i % BitsetWord::BITS as usize);
+        while self.0.len() <= idx {
+            self.0.push(0);
+        }
+        self.0[idx] |= 1 << bit;
+    }
+
+    fn get(&self, i: usize) -> bool {
+        let (idx, bit) = (i / BitsetWord::BITS as usize, i % BitsetWord::BITS as usize);
+
+        if idx >= self.0.len() {
+            return false;
+        }
+        self.0[idx] & (1 << bit) != 0
+    }
+
+    fn remove(&mut self, i: usize) {
+        let (idx, bit) = (i / BitsetWord::BITS as usize, i % BitsetWord::BITS as usize);
+        if idx < self.0.len() {
+            self.0[idx] &= !(1 << bit);
+        }
+    }
+
+    fn take_first_set(&mut self) -> Option<usize> {
+        for (i, word) in self.0.iter_mut().enumerate() {
+            if *word != 0 {
+                let bit = word.trailing_zeros();
+                *word &= !(1 << bit);
+                return Some(i * BitsetWord::BITS as usize + bit as usize);
+            }
+        }
+        None
+    }
+
+    fn count_ones(&self) -> usize {
+        self.0.iter().map(|&word| word.count_ones() as usize).sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid {
+    cells: Bitset,
+    width: usize,
+    start: (usize, usize),
+    start_direction: Direction,
+}
+
+impl Grid {
+    fn idx(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+
+    fn idx_to_point(&self, idx: usize) -> (usize, usize) {
+        (idx % self.width, idx / self.width)
+    }
+
+    fn get(&self, point: (usize, usize)) -> Option<bool> {
+        if point.0 >= self.width || point.1 >= self.width {
+            None
+        } else {
+            Some(self.cells.get(self.idx(point)))
+        }
+    }
+
+    fn set(&mut self, point: (usize, usize)) {
+        self.cells.set(self.idx(point));
+    }
+
+    fn remove(&mut self, point: (usize, usize)) {
+        self.cells.remove(self.idx(point));
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn rotated_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    fn next_point(self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+        Some(match self {
+            Self::Up => (x, y.checked_sub(1)?),
+            Self::Down => (x, y.checked_add(1)?),
+            Self::Left => (x.checked_sub(1)?, y),
+            Self::Right => (x.checked_add(1)?, y),
+        })
+    }
+}
+
+impl Day for Day6 {
+    type Parsed<'a> = Grid;
+
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const SHARES_WORK: bool = true;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("41"),
+            part2: Some("6"),
+        },
+    ];
+
+    fn generator(text: &str) -> Result<Self::Parsed<'_>, ParseError> {
+        let err = |offset, message: &str| ParseError::new(text, offset, message);
+        let input = text.as_bytes();
+        let mut cells = Bitset(ArrayVec::new());
+        let mut start_idx = None;
+        let mut direction = Direction::Up;
+
+        let width = input
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| err(input.len(), "expected a newline"))?;
+        if width == 0 || width > MAX_DIM {
+            return Err(err(width, &format!("rows must be 1 to {MAX_DIM} cells wide")));
+        }
+        // The last row may be missing its newline
+        let last_row_len = input.len() % (width + 1);
+        if last_row_len != 0 && last_row_len != width {
+            return Err(err(input.len(), &format!("expected every row to be {width} cells")));
+        }
+        let height = input.len().div_ceil(width + 1);
+        if height != width {
+            let message = format!("the grid must be square, but is {width}x{height}");
+            return Err(err(input.len(), &message));
+        }
+        for (i, &b) in input.iter().enumerate() {
+            let actual_i = i - i / (width + 1);
+            if (i % (width + 1) == width) != (b == b'\n') {
+                return Err(err(i, &format!("expected every row to be {width} cells")));
+            }
+            if matches!(b, b'^' | b'>' | b'v' | b'<') && start_idx.is_some() {
+                return Err(err(i, "found a second guard"));
+            }
+            match b {
+                b'#' => cells.set(actual_i),
+                b'^' => {
+                    start_idx = Some(actual_i);
+                    direction = Direction::Up;
+                }
+                b'>' => {
+                    start_idx = Some(actual_i);
+                    direction = Direction::Right;
+                }
+                b'v' => {
+                    start_idx = Some(actual_i);
+                    direction = Direction::Down;
+                }
+                b'<' => {
+                    start_idx = Some(actual_i);
+                    direction = Direction::Left;
+                }
+                b'.' | b'\n' => {}
+                _ => return Err(err(i, "expected one of `.#^>v<`")),
+            }
+        }
+        let start_idx = start_idx.ok_or_else(|| err(0, "expected a guard, one of `^>v<`"))?;
+
+        Ok(Grid {
+            cells,
+            width,
+            start: (start_idx % width, start_idx / width),
+            start_direction: direction,
+        })
+    }
+
+    fn validate(input: &str, _part: Option<Part>) -> Vec<ParseError> {
+        let mut violations = Vec::new();
+        let rows: Vec<_> = input.lines().collect();
+        if rows.len() > MAX_DIM {
+            let message = format!(
+                "the grid has {} rows, but at most {MAX_DIM} are supported",
+                rows.len()
+            );
+            violations.push(ParseError::at(input, rows[MAX_DIM], message));
+        }
+        for row in &rows {
+            if row.len() > MAX_DIM {
+                let message = format!(
+                    "a row is {} cells wide, but at most {MAX_DIM} are supported",
+                    row.len()
+                );
+                violations.push(ParseError::at(input, row.get(MAX_DIM..).unwrap_or(row), message));
+            } else if row.len() != rows.len() {
+                let message = format!(
+                    "the grid must be square, but this row is {} cells wide and there are {} rows",
+                    row.len(),
+                    rows.len()
+                );
+                violations.push(ParseError::at(input, row, message));
+            }
+        }
+        let guards = input.matches(['^', '>', 'v', '<']).count();
+        if guards != 1 {
+            let message = format!("there must be exactly one guard, but there are {guards}");
+            violations.push(ParseError::new(input, 0, message));
+        }
+        if violations.is_empty() {
+            if let Some(guard) = looping_guard(&rows) {
+                let message = "the guard walks in a loop instead of leaving the grid";
+                violations.push(ParseError::at(input, guard, message));
+            }
+        }
+        violations
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // Real inputs are 130x130, with an obstacle in about one cell in twenty
+        let side = crate::scaled(130, scale.sqrt()).min(MAX_DIM);
+        loop {
+            let mut cells: Vec<u8> = (0..side * side)
+                .map(|_| if rng.chance(1, 20) { b'#' } else { b'.' })
+                .collect();
+            let guard = rng.range(0..=cells.len() as u64 - 1) as usize;
+            cells[guard] = b'^';
+            let mut input = String::with_capacity(side * (side + 1));
+            for row in cells.chunks(side) {
+                input.push_str(std::str::from_utf8(row).unwrap());
+                input.push('\n');
+            }
+            // Real guards always leave, try again if this one doesn't
+            if Self::validate(&input, None).is_empty() {
+                return Some(input);
+            }
+        }
+    }
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let visited_locations = find_visited_locations(&input);
+
+        visited_locations.count_ones()
+    }
+
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let visited_locations = find_visited_locations(&input);
+
+        count_loops(input, visited_locations)
+    }
+
+    fn solve(input: Self::Parsed<'_>) -> (impl Into<Answer>, impl Into<Answer>) {
+        let visited_locations = find_visited_locations(&input);
+
+        (
+            visited_locations.count_ones(),
+            count_loops(input, visited_locations),
+        )
+    }
+}
+
+/// Counts the visited locations where a new obstruction would trap the guard in a loop
+fn count_loops(mut input: Grid, mut visited_locations: Bitset) -> u32 {
+    // The guard would notice an obstruction placed right in front of them
+    visited_locations.remove(input.idx(input.start));
+
+    let mut loops = 0;
+    while let Some(idx) = visited_locations.take_first_set() {
+        let point = input.idx_to_point(idx);
+        input.set(point);
+        loops += u32::from(run_simulation(&input) == SimResult::Loop);
+        input.remove(point);
+    }
+    loops
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum SimResult {
+    Loop,
+    Exit,
+}
+
+fn run_simulation(input: &Grid) -> SimResult {
+    let mut current_point = input.start;
+    let mut direction = input.start_direction;
+    // Bit set for each direction seen at that location
+    let mut pos_directions = [0u8; MAX_DIM * MAX_DIM];
+
+    loop {
+        let idx = input.idx(current_point);
+        let mask = 1 << direction as u8;
+        if pos_directions[idx] & mask != 0 {
+            return SimResult::Loop;
+        }
+        pos_directions[idx] |= mask;
+        let Some(next_point) = direction.next_point(current_point) else {
+            return SimResult::Exit;
+        };
+        match input.get(next_point) {
+            Some(false) => {
+                current_point = next_point;
+            }
+            Some(true) => {
+                // Only turn, there may be another obstruction in the new direction
+                direction = direction.rotated_right();
+            }
+            None => {
+                return SimResult::Exit;
+            }
+        }
+    }
+}
+
+fn find_visited_locations(input: &Grid) -> Bitset {
+    let mut visited_locations = Bitset(ArrayVec::new());
+
+    let mut current_point = input.start;
+    let mut direction = input.start_direction;
+
+    loop {
+        visited_locations.set(input.idx(current_point));
+        let Some(next_point) = direction.next_point(current_point) else {
+            break;
+        };
+        match input.get(next_point) {
+            Some(false) => {
+                current_point = next_point;
+            }
+            Some(true) => {
+                direction = direction.rotated_right();
+            }
+            None => {
+                break;
+            }
+        }
+    }
+    visited_locations
+}
+
+const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+/// Walks the guard on a square grid with one guard, returning the guard if it never leaves
+fn looping_guard<'a>(rows: &[&'a str]) -> Option<&'a str> {
+    let width = rows.len();
+    let (y, row) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.contains(['^', '>', 'v', '<']))?;
+    let x = row.find(['^', '>', 'v', '<'])?;
+    let mut direction = match row.as_bytes()[x] {
+        b'^' => Direction::Up,
+        b'>' => Direction::Right,
+        b'v' => Direction::Down,
+        _ => Direction::Left,
+    };
+    let mut point = (x, y);
+    let mut seen = vec![false; width * width * 4];
+    loop {
+        let state = (point.1 * width + point.0) * 4 + direction as usize;
+        if std::mem::replace(&mut seen[state], true) {
+            return Some(&row[x..]);
+        }
+        let next = direction.next_point(point)?;
+        match rows.get(next.1)?.as_bytes().get(next.0)? {
+            b'#' => direction = direction.rotated_right(),
+            _ => point = next,
+        }
+    }
+}
+
+crate::codspeed_def!(Day6);
+
+#[test]
+fn validate_assumptions() {
+    assert!(Day6::validate("..\n^.\n", None).is_empty());
+    let violations = Day6::validate("...\n^..\n", None);
+    assert_eq!(violations.len(), 2);
+    assert!(violations[0].message.contains("square"));
+    assert_eq!(Day6::validate("..\n..\n", None).len(), 1);
+    let boxed_in = Day6::validate(".#.\n#^#\n.#.\n", None);
+    assert_eq!((boxed_in.len(), boxed_in[0].column), (1, 2));
+}
+
+#[test]
+fn turns_twice_in_a_row() {
+    // Blocked ahead and to the right, so the guard turns twice before stepping down and out
+    let input = ".#.\n.^#\n...\n";
+    assert!(Day6::validate(input, None).is_empty());
+    let parsed = Day6::generator(input).unwrap();
+    assert_eq!(Day6::part1(parsed.clone()).into(), Answer::from(2_u32));
+    assert_eq!(Day6::part2(parsed).into(), Answer::from(0_u32));
+}
@@ -0,0 +1,312 @@
+use crate::{Answer, Day, Example, ParseError, Part, Rng};
+use arrayvec::ArrayVec;
+
+type Int = u64;
+type Offset = u32;
+/// Part 2 keeps the gaps of each size in an `ArrayVec`
+const MAX_GAPS_PER_SIZE: usize = 1024 * 2;
+
+pub struct Day9;
+
+impl Day for Day9 {
+    type Parsed<'a> = &'a [u8];
+
+    const ALLOCATION_FREE: &'static [Part] = &[Part::One, Part::Two];
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: "2333133121414131402\n",
+            part1: Some("1928"),
+            part2: Some("2858"),
+        },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Parsed<'_>, ParseError> {
+        let disk_map = input.strip_suffix('\n').unwrap_or(input).as_bytes();
+        if disk_map.is_empty() {
+            return Err(ParseError::new(input, 0, "expected a disk map"));
+        }
+        for (i, &b) in disk_map.iter().enumerate() {
+            let is_file = i % 2 == 0;
+            if !b.is_ascii_digit() {
+                return Err(ParseError::new(input, i, "expected a digit"));
+            }
+            if is_file && b == b'0' {
+                return Err(ParseError::new(input, i, "files must have a length of at least 1"));
+            }
+        }
+        // Free space after the last file changes nothing
+        let files_end = disk_map.len() - usize::from(disk_map.len() % 2 == 0);
+        Ok(&disk_map[..files_end])
+    }
+
+    fn validate(input: &str, part: Option<Part>) -> Vec<ParseError> {
+        // Only part 2 keeps track of gaps by size
+        let moves_files = part != Some(Part::One);
+        let mut gaps_by_size = [0; 9];
+        for gap in input.bytes().skip(1).step_by(2) {
+            if let b'1'..=b'9' = gap {
+                gaps_by_size[usize::from(gap - b'1')] += 1;
+            }
+        }
+        let mut violations: Vec<_> = gaps_by_size
+            .iter()
+            .zip(1..)
+            .filter(|&(&count, _)| moves_files && count > MAX_GAPS_PER_SIZE)
+            .map(|(count, size)| {
+                let message = format!(
+                    "there are {count} gaps of size {size}, but at most {MAX_GAPS_PER_SIZE} are supported"
+                );
+                ParseError::new(input, 0, message)
+            })
+            .collect();
+
+        // Malformed input is left to the generator
+        let Ok(disk_map) = Self::generator(input) else {
+            return violations;
+        };
+        // The checksum is at most every block's position times the largest file id
+        let blocks: u64 = disk_map.iter().map(|&b| u64::from(b - b'0')).sum();
+        let max_id = disk_map.len() as u64 / 2;
+        if u128::from(blocks).pow(2) * u128::from(max_id) > u128::from(Int::MAX) {
+            let message = "the disk map is too large for the checksum to fit in 64 bits";
+            violations.push(ParseError::new(input, 0, message));
+        } else if moves_files && violations.is_empty() && compact_files(disk_map).is_none() {
+            // Moving files splits gaps, which can fill up a size that had room to start with
+            let message = format!(
+                "moving files leaves more than {MAX_GAPS_PER_SIZE} gaps of one size, which part 2 doesn't support"
+            );
+            violations.push(ParseError::new(input, 0, message));
+        }
+        violations
+    }
+
+    fn generate_input(rng: &mut Rng, scale: f64) -> Option<String> {
+        // Real inputs have 10000 files
+        let files = crate::scaled(10_000, scale);
+        let mut gaps_by_size = [0; 9];
+        let mut input = String::with_capacity(files * 2);
+        for i in 0..files {
+            input.push(char::from(b'0' + rng.range(1..=9) as u8));
+            if i + 1 == files {
+                break;
+            }
+            let mut gap = rng.range(0..=9) as u8;
+            // Leave room for the gaps part 2 splits off bigger ones
+            if gap > 0 {
+                let count = &mut gaps_by_size[usize::from(gap - 1)];
+                if *count < MAX_GAPS_PER_SIZE / 2 {
+                    *count += 1;
+                } else {
+                    gap = 0;
+                }
+            }
+            input.push(char::from(b'0' + gap));
+        }
+        input.push('\n');
+        Some(input)
+    }
+
+    fn part1(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        let mut it = input.iter().copied().map(|b| b - b'0');
+        let mut hash = 0;
+
+        let mut current_value = 0;
+        let mut current_index = 0;
+        let mut end_value = (input.len() / 2) as Int;
+        let mut end_len = it.next_back().unwrap();
+
+        'outer: loop {
+            let Some(start_len) = it.next() else { break };
+            hash += hash_range(current_value, current_index, start_len);
+            current_index += Offset::from(start_len);
+            current_value += 1;
+            let Some(mut start_gap) = it.next() else {
+                break;
+            };
+            while end_len < start_gap {
+                hash += hash_range(end_value, current_index, end_len);
+                current_index += Offset::from(end_len);
+                start_gap -= end_len;
+                end_value -= 1;
+                _ = it.next_back();
+                let Some(new_end_len) = it.next_back() else {
+                    // Every file has been placed
+                    end_len = 0;
+                    break 'outer;
+                };
+                end_len = new_end_len;
+            }
+            hash += hash_range(end_value, current_index, start_gap);
+            end_len -= start_gap;
+            current_index += Offset::from(start_gap);
+        }
+        hash += hash_range(end_value, current_index, end_len);
+
+        hash
+    }
+
+    fn part2(input: Self::Parsed<'_>) -> impl Into<Answer> {
+        compact_files(input).expect("too many gaps of one size, see `Day9::validate`")
+    }
+}
+
+/// Moves whole files to the leftmost gap they fit in, returning the checksum, or `None` if there
+/// are ever more than [`MAX_GAPS_PER_SIZE`] gaps of one size
+fn compact_files(input: &[u8]) -> Option<Int> {
+    let mut gap_offsets_by_size: [ArrayVec<Offset, MAX_GAPS_PER_SIZE>; 9] =
+        [const { ArrayVec::new_const() }; 9];
+    let mut it = input.iter().copied().map(|b| b - b'0');
+    let mut end_offset;
+    {
+        let mut it = it.clone();
+        let mut current_offset = 0;
+        loop {
+            let Some(val_len) = it.next() else { break };
+            current_offset += Offset::from(val_len);
+            let Some(gap) = it.next() else { break };
+            if gap == 0 {
+                continue;
+            }
+            gap_offsets_by_size[usize::from(gap - 1)]
+                .try_push(current_offset)
+                .ok()?;
+            current_offset += Offset::from(gap);
+        }
+        end_offset = current_offset;
+        for gap_offsets in gap_offsets_by_size.iter_mut() {
+            gap_offsets.reverse();
+        }
+    }
+
+    let mut hash = 0;
+    let mut end_value = (input.len() / 2) as Int;
+    loop {
+        let Some(end_len) = it.next_back() else { break };
+        end_offset -= Offset::from(end_len);
+
+        let result = gap_offsets_by_size
+            .iter_mut()
+            .enumerate()
+            .map(|(i, gap_offsets)| {
+                let gap_len = i as u8 + 1;
+                (gap_len, gap_offsets)
+            })
+            .skip(usize::from(end_len - 1))
+            .filter(|(_, gap_offsets)| gap_offsets.last().map_or(false, |&x| x < end_offset))
+            .min_by_key(|(_, gap_offsets)| gap_offsets.last().copied().unwrap_or(Offset::MAX));
+        let mut new_offset = end_offset;
+        if let Some((new_gap_len, min_gap_offset)) = result {
+            new_offset = min_gap_offset.pop().unwrap();
+            let remaining_gap = new_gap_len - end_len;
+            if remaining_gap > 0 {
+                let gap_offsets = &mut gap_offsets_by_size[usize::from(remaining_gap - 1)];
+                let new_offset = new_offset + Offset::from(end_len);
+                // Get first index where !(x > new_offset)
+                let to_insert_at = gap_offsets.partition_point(|&x| x > new_offset);
+                gap_offsets.try_insert(to_insert_at, new_offset).ok()?;
+            }
+        }
+        hash += hash_range(end_value, new_offset, end_len);
+
+        let Some(end_gap) = it.next_back() else { break };
+        end_value -= 1;
+        end_offset -= Offset::from(end_gap);
+    }
+
+    Some(hash)
+}
+
+fn hash_range(value: Int, start: Offset, len: u8) -> Int {
+    if len == 0 {
+        return 0;
+    }
+    let max = Int::from(start) + Int::from(len - 1);
+    let avg = Int::from(start) + Int::from(max);
+    let mut range_sum = avg * (Int::from(len) / 2);
+    if len % 2 != 0 {
+        range_sum += Int::from(avg / 2);
+    }
+
+    value * range_sum
+}
+
+crate::codspeed_def!(Day9);
+
+#[test]
+fn hash_range_simple() {
+    assert_eq!(hash_range(1, 1, 1), 1);
+    assert_eq!(hash_range(1, 1, 100), 5050);
+    assert_eq!(hash_range(2, 1, 100), 10100);
+    assert_eq!(hash_range(1, 1, 101), 5151);
+    assert_eq!(hash_range(1, 50, 50), 3725);
+}
+
+#[test]
+fn validate_gap_capacity() {
+    assert!(Day9::validate("2333133121414131402\n", None).is_empty());
+    let too_many_gaps = "13".repeat(MAX_GAPS_PER_SIZE + 1);
+    let violations = Day9::validate(&too_many_gaps, None);
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].message.contains("gaps of size 3"));
+    assert!(Day9::validate(&too_many_gaps, Some(Part::One)).is_empty());
+}
+
+/// Lays out every block, then moves blocks (part 1) or whole files (part 2) one at a time
+#[cfg(test)]
+fn reference(input: &str) -> (u64, u64) {
+    let mut blocks: Vec<Option<u64>> = Vec::new();
+    for (i, len) in input.trim_end().bytes().map(|b| b - b'0').enumerate() {
+        let id = (i % 2 == 0).then_some(i as u64 / 2);
+        blocks.extend(std::iter::repeat_n(id, len.into()));
+    }
+    let checksum = |blocks: &[Option<u64>]| -> u64 {
+        (0..)
+            .zip(blocks)
+            .map(|(pos, id)| pos * id.unwrap_or(0))
+            .sum()
+    };
+
+    let mut compacted = blocks.clone();
+    let (mut free, mut last) = (0, compacted.len() - 1);
+    loop {
+        while compacted[free].is_some() && free < last {
+            free += 1;
+        }
+        while compacted[last].is_none() && last > free {
+            last -= 1;
+        }
+        if free >= last {
+            break;
+        }
+        compacted.swap(free, last);
+    }
+    let part1 = checksum(&compacted);
+
+    let files = blocks.iter().flatten().max().map_or(0, |&id| id + 1);
+    for id in (0..files).rev() {
+        let start = blocks.iter().position(|&b| b == Some(id)).unwrap();
+        let len = blocks[start..].iter().take_while(|&&b| b == Some(id)).count();
+        let gap = (0..start).find(|&i| blocks[i..i + len].iter().all(Option::is_none));
+        if let Some(gap) = gap {
+            for i in 0..len {
+                blocks.swap(gap + i, start + i);
+            }
+        }
+    }
+    (part1, checksum(&blocks))
+}
+
+#[test]
+fn trailing_free_space() {
+    // Real inputs end with a file, but free space after it doesn't change anything
+    let input = "23331331214141314025\n";
+    assert!(Day9::validate(input, None).is_empty());
+    assert_eq!(crate::part_1_impl::<Day9>(input), Answer::from(1928_u64));
+    assert_eq!(crate::part_2_impl::<Day9>(input), Answer::from(2858_u64));
+}
+
+#[test]
+fn matches_reference() {
+    crate::check_against_reference::<Day9, _, _>(2000, 0.01, reference);
+}